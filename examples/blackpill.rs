@@ -108,7 +108,7 @@ fn main() -> ! {
 
     cs.set_high().unwrap();
     let mut accelerometer = Adxl355::new(spi, cs, &cfg).unwrap();
-    let id = accelerometer.get_device_id();
+    let id = accelerometer.get_device_id().unwrap();
 
     writeln!(tx, "Got device ID {}", id).unwrap();
     if id == 0xED {
@@ -120,7 +120,7 @@ fn main() -> ! {
     drdy.trigger_on_edge(&dp.EXTI, Edge::RISING);
     drdy.enable_interrupt(&dp.EXTI);
 
-    accelerometer.start();
+    let mut accelerometer = accelerometer.start().map_err(|(e, _)| e).unwrap();
 
     loop {
 
@@ -83,20 +83,20 @@ fn main() -> ! {
        .range(Range::_4G);
 
     let mut accelerometer = Adxl355::new(spi, cs, &cfg).unwrap();
-    let id = accelerometer.get_device_id();
+    let id = accelerometer.get_device_id().unwrap();
 
     writeln!(usart, "Got device ID {}", id).unwrap();
     if id == 0xED {
         led.set_high().unwrap();
     }
 
+    let mut accelerometer = accelerometer.start().map_err(|(e, _)| e).unwrap();
+
     // verify sample rate
     let fs: f32 = accelerometer.sample_rate().unwrap();
 
     writeln!(usart, "Sample rate = {} [Hz]", fs).unwrap();
 
-    accelerometer.start();
-
     writeln!(usart, "Start!").unwrap();
 
     loop {
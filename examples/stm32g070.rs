@@ -81,18 +81,18 @@ fn main() -> ! {
        .range(Range::_4G);
 
     let mut accelerometer = Adxl355::new(spi, cs, &cfg).unwrap();
-    let id = accelerometer.get_device_id();
+    let id = accelerometer.get_device_id().unwrap();
 
     writeln!(usart, "Got device ID {}", id).unwrap();
     if id == 0xED {
         led.set_high().unwrap();
     }
 
-    accelerometer.start();
+    let mut accelerometer = accelerometer.start().map_err(|(e, _)| e).unwrap();
 
     writeln!(usart, "Start!").unwrap();
 
-    let temp: u16 = accelerometer.read_temp_raw();
+    let temp: u16 = accelerometer.read_temp_raw().unwrap();
 
     writeln!(usart, "Temp: {}", temp).unwrap();
 
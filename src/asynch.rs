@@ -0,0 +1,346 @@
+//! Async driver on [embedded-hal-async]
+//!
+//! [`Adxl355`] mirrors the blocking [`crate::Adxl355`] driver with `async fn`
+//! register access, so FIFO drains and status polling don't stall the executor.
+//! It is available with the `async` feature.
+//!
+//! ```ignore
+//! use adxl355::{Config, SpiDeviceInterface};
+//! use adxl355::asynch::Adxl355;
+//!
+//! let accelerometer = Adxl355::new(SpiDeviceInterface::new(spi), &Config::new()).await?;
+//! let mut accelerometer = accelerometer.start().await.map_err(|(e, _)| e)?;
+//!
+//! loop {
+//!     accelerometer.wait_for_data_ready(&mut drdy).await.unwrap();
+//!     let accel = accelerometer.accel_raw().await?;
+//! }
+//! ```
+//!
+//! [embedded-hal-async]: https://docs.rs/embedded-hal-async
+
+use core::convert::Infallible;
+use core::marker::PhantomData;
+
+use embedded_hal_async::digital::Wait;
+use embedded_hal_async::i2c::I2c;
+use embedded_hal_async::spi::{Operation, SpiDevice};
+
+use crate::interface::{I2C_WRITE_MAX, SPI_READ, SPI_WRITE};
+use crate::register::Register;
+use crate::settings::*;
+use crate::variant::{self, Variant};
+use crate::{decode_fifo, fifo_sample, parse_sample};
+use crate::{Config, DeviceInfo, Error, I2cInterface, I32x3, InterruptPolarity, Measuring, Mode, SpiDeviceInterface, Standby, Status};
+#[cfg(feature = "float")]
+use crate::F32x3;
+
+/// Register access over an async serial bus
+#[allow(async_fn_in_trait)]
+pub trait Interface {
+    /// Bus error type
+    type BusError;
+
+    /// Reads consecutive registers starting at `reg` into `buffer`.
+    ///
+    /// Reading FIFO_DATA does not advance the register address, so `buffer`
+    /// is filled with consecutive FIFO entries instead.
+    async fn read(&mut self, reg: u8, buffer: &mut [u8]) -> Result<(), Error<Self::BusError, Infallible>>;
+
+    /// Writes `data` to consecutive registers starting at `reg`
+    async fn write(&mut self, reg: u8, data: &[u8]) -> Result<(), Error<Self::BusError, Infallible>>;
+
+    /// Returns `true` if the I2C_HS bit in RANGE has to be set
+    fn high_speed(&self) -> bool {
+        false
+    }
+}
+
+impl<SPI: SpiDevice> Interface for SpiDeviceInterface<SPI> {
+    type BusError = SPI::Error;
+
+    async fn read(&mut self, reg: u8, buffer: &mut [u8]) -> Result<(), Error<SPI::Error, Infallible>> {
+        let header = [(reg << 1) | SPI_READ];
+        self.spi.transaction(&mut [Operation::Write(&header), Operation::Read(buffer)]).await
+            .map_err(Error::Bus)
+    }
+
+    async fn write(&mut self, reg: u8, data: &[u8]) -> Result<(), Error<SPI::Error, Infallible>> {
+        let header = [(reg << 1) | SPI_WRITE];
+        self.spi.transaction(&mut [Operation::Write(&header), Operation::Write(data)]).await
+            .map_err(Error::Bus)
+    }
+}
+
+impl<I2C: I2c> Interface for I2cInterface<I2C> {
+    type BusError = I2C::Error;
+
+    async fn read(&mut self, reg: u8, buffer: &mut [u8]) -> Result<(), Error<I2C::Error, Infallible>> {
+        self.i2c.write_read(self.address.addr(), &[reg], buffer).await.map_err(Error::Bus)
+    }
+
+    async fn write(&mut self, reg: u8, data: &[u8]) -> Result<(), Error<I2C::Error, Infallible>> {
+        if data.len() > I2C_WRITE_MAX {
+            return Err(Error::InvalidParam);
+        }
+        let mut bytes = [0u8; 1 + I2C_WRITE_MAX];
+        bytes[0] = reg;
+        bytes[1..1 + data.len()].copy_from_slice(data);
+        self.i2c.write(self.address.addr(), &bytes[..1 + data.len()]).await.map_err(Error::Bus)
+    }
+
+    fn high_speed(&self) -> bool {
+        self.high_speed
+    }
+}
+
+/// Async ADXL35x driver
+///
+/// The parameters match the blocking [`crate::Adxl355`]. The configuration is
+/// changed with [`apply`](Self::apply), which puts the device in standby while
+/// writing when called in [`Measuring`].
+pub struct Adxl355<IF, MODE = Standby, V: Variant = variant::Adxl355> {
+    interface: IF,
+    settings: Settings<V>,
+    mode: PhantomData<MODE>,
+}
+
+impl<IF, E, V> Adxl355<IF, Standby, V>
+where
+    IF: Interface<BusError = E>,
+    V: Variant
+{
+    /// Takes a bus interface and a config object to initialize the driver.
+    ///
+    /// Fails with [`Error::WrongDevice`] if the identification registers
+    /// do not match the selected part.
+    pub async fn new(interface: IF, config: &Config<V>) -> Result<Self, Error<E, Infallible>> {
+        let mut adxl355 = Adxl355 {
+            interface,
+            settings: Settings::new(config).ok_or(Error::InvalidParam)?,
+            mode: PhantomData,
+        };
+
+        let info = adxl355.device_info().await?;
+
+        if !info.is_variant::<V>() {
+            return Err(Error::WrongDevice(info));
+        }
+
+        adxl355.configure().await?;
+
+        Ok(adxl355)
+    }
+
+    /// Puts the device in `Measurement mode`, leaving the other POWER_CTL bits untouched.
+    ///
+    /// If the POWER_CTL write fails, the driver is returned with the error.
+    pub async fn start(mut self) -> Result<Adxl355<IF, Measuring, V>, (Error<E, Infallible>, Self)> {
+        match self.modify_reg(Register::POWER_CTL.addr(), POWER_CTL_STANDBY, 0).await {
+            Ok(()) => Ok(self.into_mode()),
+            Err(e) => Err((e, self)),
+        }
+    }
+}
+
+impl<IF, E, V> Adxl355<IF, Measuring, V>
+where
+    IF: Interface<BusError = E>,
+    V: Variant
+{
+    /// Puts the device in `Standby mode`, leaving the other POWER_CTL bits untouched.
+    ///
+    /// If the POWER_CTL write fails, the driver is returned with the error.
+    pub async fn stop(mut self) -> Result<Adxl355<IF, Standby, V>, (Error<E, Infallible>, Self)> {
+        match self.modify_reg(Register::POWER_CTL.addr(), POWER_CTL_STANDBY, POWER_CTL_STANDBY).await {
+            Ok(()) => Ok(self.into_mode()),
+            Err(e) => Err((e, self)),
+        }
+    }
+
+    /// Gets the raw acceleration reading
+    pub async fn accel_raw(&mut self) -> Result<I32x3, Error<E, Infallible>> {
+        let mut bytes = [0u8; 9];
+        self.read_reg(Register::XDATA3.addr(), &mut bytes).await?;
+
+        let x = parse_sample(&bytes[0..3]);
+        let y = parse_sample(&bytes[3..6]);
+        let z = parse_sample(&bytes[6..9]);
+
+        Ok(I32x3::new(x, y, z))
+    }
+
+    /// Gets the acceleration in µg, using integer math only
+    pub async fn accel_micro_g(&mut self) -> Result<I32x3, Error<E, Infallible>> {
+        let raw = self.accel_raw().await?;
+        Ok(self.settings.micro_g(raw))
+    }
+
+    /// Gets the acceleration in g, scaled by the nominal sensitivity of the
+    /// active range and corrected by the scale calibration
+    #[cfg(feature = "float")]
+    pub async fn accel_norm(&mut self) -> Result<F32x3, Error<E, Infallible>> {
+        let raw = self.accel_raw().await?;
+        Ok(self.settings.norm(raw))
+    }
+
+    /// Waits until the DRDY pin signals a new sample.
+    ///
+    /// DRDY is active high and cleared when the data registers are read, so this
+    /// returns immediately if a sample is pending.
+    pub async fn wait_for_data_ready<P: Wait>(&mut self, drdy: &mut P) -> Result<(), P::Error> {
+        drdy.wait_for_high().await
+    }
+
+    /// Waits until an INT pin is asserted, using the configured interrupt polarity.
+    ///
+    /// Use this instead of [`wait_for_data_ready`](Self::wait_for_data_ready) when
+    /// DATA_RDY is routed to INT1 or INT2 through the interrupt map.
+    pub async fn wait_for_interrupt<P: Wait>(&mut self, int: &mut P) -> Result<(), P::Error> {
+        match self.settings.int_pol {
+            InterruptPolarity::ActiveHigh => int.wait_for_high().await,
+            InterruptPolarity::ActiveLow => int.wait_for_low().await,
+        }
+    }
+}
+
+impl<IF, E, MODE, V> Adxl355<IF, MODE, V>
+where
+    IF: Interface<BusError = E>,
+    MODE: Mode,
+    V: Variant
+{
+    /// Applies the values set in `config`, leaving the other settings unchanged.
+    ///
+    /// In the `Measuring` state the device is put in standby while the registers are written.
+    /// The driver settings are only updated once all registers are written.
+    pub async fn apply(&mut self, config: &Config<V>) -> Result<(), Error<E, Infallible>> {
+        let settings = self.settings.with_config(config).ok_or(Error::InvalidParam)?;
+
+        if MODE::MEASURING {
+            self.modify_reg(Register::POWER_CTL.addr(), POWER_CTL_STANDBY, POWER_CTL_STANDBY).await?;
+        }
+        let result = self.write_settings(&settings).await;
+        if result.is_ok() {
+            self.settings = settings;
+        }
+        if MODE::MEASURING {
+            self.modify_reg(Register::POWER_CTL.addr(), POWER_CTL_STANDBY, 0).await?;
+        }
+        result
+    }
+
+    /// Reads the configuration registers back from the device, see [`crate::Adxl355::read_config`]
+    pub async fn read_config(&mut self) -> Result<Config<V>, Error<E, Infallible>> {
+        let mut regs = [0u8; CONFIG_REGS];
+        self.read_reg(Register::OFFSET_X_H.addr(), &mut regs).await?;
+        self.settings.decode(&regs).map_err(Error::InvalidData)
+    }
+
+    /// Output data rate in Hz
+    #[cfg(feature = "float")]
+    pub fn sample_rate(&self) -> f32 {
+        self.settings.sample_rate()
+    }
+
+    /// Returns the raw contents of the temperature registers
+    pub async fn read_temp_raw(&mut self) -> Result<u16, Error<E, Infallible>> {
+        let mut bytes = [0u8; 2];
+        self.read_reg(Register::TEMP2.addr(), &mut bytes).await?;
+
+        let temp_h = ((bytes[0] & 0x0F) as u16) << 8;
+        let temp_l = (bytes[1] as u16) & 0x00FF;
+
+        Ok(temp_h | temp_l)
+    }
+
+    /// Reads the STATUS register
+    pub async fn status(&mut self) -> Result<Status, Error<E, Infallible>> {
+        let mut output = [0u8];
+        self.read_reg(Register::STATUS.addr(), &mut output).await?;
+        Ok(Status::from_bits_truncate(output[0]))
+    }
+
+    /// Returns the number of valid entries in the FIFO. Each axis counts as one entry.
+    pub async fn fifo_entries(&mut self) -> Result<u8, Error<E, Infallible>> {
+        let mut output = [0u8];
+        self.read_reg(Register::FIFO_ENTRIES.addr(), &mut output).await?;
+        Ok(output[0] & 0x7F)
+    }
+
+    /// Drains complete x, y, z samples from the FIFO into `samples`,
+    /// see [`crate::Adxl355::read_fifo`]. Returns the number of samples written.
+    pub async fn read_fifo(&mut self, samples: &mut [I32x3]) -> Result<usize, Error<E, Infallible>> {
+        let available = self.fifo_entries().await? as usize / 3;
+        let count = available.min(samples.len());
+        if count == 0 {
+            return Ok(0);
+        }
+
+        let mut bytes = [0u8; FIFO_CAPACITY * 3];
+        let entries = &mut bytes[..count * 9];
+        self.read_reg(Register::FIFO_DATA.addr(), entries).await?;
+
+        let (mut n, partial) = decode_fifo(entries, samples);
+        if !partial.is_empty() {
+            // read the remaining entries of the last sample to realign the FIFO
+            let mut chunk = [0u8; 9];
+            chunk[..partial.len()].copy_from_slice(partial);
+
+            self.read_reg(Register::FIFO_DATA.addr(), &mut chunk[partial.len()..]).await?;
+
+            if let Some(sample) = fifo_sample(&chunk) {
+                samples[n] = sample;
+                n += 1;
+            }
+        }
+
+        Ok(n)
+    }
+
+    /// Reads the identification registers `DEVID_AD`, `DEVID_MST`, `DEVID` and `REVID`
+    pub async fn device_info(&mut self) -> Result<DeviceInfo, Error<E, Infallible>> {
+        let mut bytes = [0u8; 4];
+        self.read_reg(Register::DEVID_AD.addr(), &mut bytes).await?;
+
+        Ok(DeviceInfo {
+            ad_id: bytes[0],
+            mems_id: bytes[1],
+            part_id: bytes[2],
+            revision: bytes[3],
+        })
+    }
+
+    async fn configure(&mut self) -> Result<(), Error<E, Infallible>> {
+        let settings = self.settings;
+        self.write_settings(&settings).await
+    }
+
+    /// Writes all configuration registers from `settings`
+    async fn write_settings(&mut self, settings: &Settings<V>) -> Result<(), Error<E, Infallible>> {
+        let regs = settings.config_regs(MODE::MEASURING, self.interface.high_speed());
+        for &(first, last) in CONFIG_SEQUENCE.iter() {
+            self.interface.write(first.addr(), &regs[config_index(first)..=config_index(last)]).await?;
+        }
+        Ok(())
+    }
+
+    fn into_mode<M: Mode>(self) -> Adxl355<IF, M, V> {
+        Adxl355 {
+            interface: self.interface,
+            settings: self.settings,
+            mode: PhantomData,
+        }
+    }
+
+    /// Read-modify-write of the bits in `mask`
+    async fn modify_reg(&mut self, reg: u8, mask: u8, value: u8) -> Result<(), Error<E, Infallible>> {
+        let mut output = [0u8];
+        self.read_reg(reg, &mut output).await?;
+        self.interface.write(reg, &[(output[0] & !mask) | (value & mask)]).await
+    }
+
+    async fn read_reg(&mut self, reg: u8, buffer: &mut [u8]) -> Result<(), Error<E, Infallible>> {
+        self.interface.read(reg, buffer).await
+    }
+}
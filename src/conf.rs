@@ -1,6 +1,14 @@
 #![allow(non_camel_case_types)]
 
-#[derive(Copy, Clone, Debug)]
+use core::convert::TryFrom;
+
+use accelerometer::vector::I32x3;
+#[cfg(feature = "float")]
+use accelerometer::vector::F32x3;
+
+use crate::variant::{self, RangeSetting, Variant};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Range {
     _2G = 0b01,
     _4G = 0b10,
@@ -11,8 +19,18 @@ impl Range {
     pub fn val(self) -> u8 {
         self as u8
     }
+
+    /// Nominal sensitivity in LSB/g
+    pub fn lsb_per_g(self) -> u32 {
+        match self {
+            Range::_2G => 256_000,
+            Range::_4G => 128_000,
+            Range::_8G => 64_000,
+        }
+    }
 }
 
+#[cfg(feature = "float")]
 impl From<Range> for f32 {
     fn from(range: Range) -> f32 {
         match range {
@@ -23,13 +41,84 @@ impl From<Range> for f32 {
     }
 }
 
+impl TryFrom<u8> for Range {
+    type Error = u8;
+
+    /// Decodes the range bits of the RANGE register
+    fn try_from(val: u8) -> Result<Self, u8> {
+        match val {
+            0b01 => Ok(Range::_2G),
+            0b10 => Ok(Range::_4G),
+            0b11 => Ok(Range::_8G),
+            _ => Err(val),
+        }
+    }
+}
+
 impl Default for Range {
     fn default() -> Self {
         Range::_2G
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+/// Measurement range of the ADXL357
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Range357 {
+    /// ±10.24 g
+    _10G = 0b01,
+    /// ±20.48 g
+    _20G = 0b10,
+    /// ±40.96 g
+    _40G = 0b11,
+}
+
+impl Default for Range357 {
+    fn default() -> Self {
+        Range357::_10G
+    }
+}
+
+impl Range357 {
+    pub fn val(self) -> u8 {
+        self as u8
+    }
+
+    /// Nominal sensitivity in LSB/g
+    pub fn lsb_per_g(self) -> u32 {
+        match self {
+            Range357::_10G => 51_200,
+            Range357::_20G => 25_600,
+            Range357::_40G => 12_800,
+        }
+    }
+}
+
+#[cfg(feature = "float")]
+impl From<Range357> for f32 {
+    fn from(range: Range357) -> f32 {
+        match range {
+            Range357::_10G => 10.24,
+            Range357::_20G => 20.48,
+            Range357::_40G => 40.96,
+        }
+    }
+}
+
+impl TryFrom<u8> for Range357 {
+    type Error = u8;
+
+    /// Decodes the range bits of the RANGE register
+    fn try_from(val: u8) -> Result<Self, u8> {
+        match val {
+            0b01 => Ok(Range357::_10G),
+            0b10 => Ok(Range357::_20G),
+            0b11 => Ok(Range357::_40G),
+            _ => Err(val),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 /// Output data rate (odr) and Low pass filter corner frequency (lpf)
 pub enum ODR_LPF {
     /// odr = 4000 Hz and lpf = 1000 Hz
@@ -62,6 +151,7 @@ impl ODR_LPF {
     }
 }
 
+#[cfg(feature = "float")]
 impl From<ODR_LPF> for f32 {
     fn from(rate: ODR_LPF) -> f32 {
         match rate {
@@ -80,13 +170,35 @@ impl From<ODR_LPF> for f32 {
     }
 }
 
+impl TryFrom<u8> for ODR_LPF {
+    type Error = u8;
+
+    /// Decodes the ODR_LPF bits of the FILTER register
+    fn try_from(val: u8) -> Result<Self, u8> {
+        match val {
+            0 => Ok(ODR_LPF::ODR_4000_Hz),
+            1 => Ok(ODR_LPF::ODR_2000_Hz),
+            2 => Ok(ODR_LPF::ODR_1000_Hz),
+            3 => Ok(ODR_LPF::ODR_500_Hz),
+            4 => Ok(ODR_LPF::ODR_250_Hz),
+            5 => Ok(ODR_LPF::ODR_125_Hz),
+            6 => Ok(ODR_LPF::ODR_62_5_Hz),
+            7 => Ok(ODR_LPF::ODR_31_25_Hz),
+            8 => Ok(ODR_LPF::ODR_15_625_Hz),
+            9 => Ok(ODR_LPF::ODR_7_813_Hz),
+            10 => Ok(ODR_LPF::ODR_3_906_Hz),
+            _ => Err(val),
+        }
+    }
+}
+
 impl Default for ODR_LPF {
     fn default() -> Self {
         ODR_LPF::ODR_3_906_Hz
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 /// High pass corner frequency is proportional to the output data rate (ODR)
 pub enum HPF_CORNER {
     /// no high pass filter
@@ -111,33 +223,378 @@ impl HPF_CORNER {
     }
 }
 
+impl TryFrom<u8> for HPF_CORNER {
+    type Error = u8;
+
+    /// Decodes the HPF_CORNER bits of the FILTER register
+    fn try_from(val: u8) -> Result<Self, u8> {
+        match val {
+            0 => Ok(HPF_CORNER::NONE),
+            1 => Ok(HPF_CORNER::_247_ODR),
+            2 => Ok(HPF_CORNER::_62_084_ODR),
+            3 => Ok(HPF_CORNER::_15_545_ODR),
+            4 => Ok(HPF_CORNER::_3_862_ODR),
+            5 => Ok(HPF_CORNER::_0_954_ODR),
+            6 => Ok(HPF_CORNER::_0_238_ODR),
+            _ => Err(val),
+        }
+    }
+}
+
 impl Default for HPF_CORNER {
     fn default() -> Self {
         HPF_CORNER::NONE
     }
 }
 
-pub struct Config {
-    pub(crate) range: Option<Range>,
+/// External synchronization mode (EXT_SYNC bits of the SYNC register)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SyncMode {
+    /// internal synchronization
+    Internal                 = 0b00,
+    /// external synchronization, no interpolation filter
+    External                 = 0b01,
+    /// external synchronization, interpolation filter enabled
+    ExternalInterpolated     = 0b10,
+}
+
+impl Default for SyncMode {
+    fn default() -> Self {
+        SyncMode::Internal
+    }
+}
+
+impl SyncMode {
+    pub fn val(self) -> u8 {
+        self as u8
+    }
+}
+
+impl TryFrom<u8> for SyncMode {
+    type Error = u8;
+
+    /// Decodes the EXT_SYNC bits of the SYNC register
+    fn try_from(val: u8) -> Result<Self, u8> {
+        match val {
+            0b00 => Ok(SyncMode::Internal),
+            0b01 => Ok(SyncMode::External),
+            0b10 => Ok(SyncMode::ExternalInterpolated),
+            _ => Err(val),
+        }
+    }
+}
+
+/// Operating mode of the device (STANDBY bit of the POWER_CTL register)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PowerState {
+    /// no measurements are made, the default after power up
+    Standby,
+    /// measurements are made at the output data rate
+    Measurement,
+}
+
+/// Interrupt pin(s) an interrupt source is routed to
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InterruptPin {
+    /// not routed to any pin
+    None  = 0b00,
+    /// routed to INT1
+    Int1  = 0b01,
+    /// routed to INT2
+    Int2  = 0b10,
+    /// routed to both INT1 and INT2
+    Both  = 0b11,
+}
+
+impl Default for InterruptPin {
+    fn default() -> Self {
+        InterruptPin::None
+    }
+}
+
+impl InterruptPin {
+    pub fn val(self) -> u8 {
+        self as u8
+    }
+
+    fn from_bits(int1: bool, int2: bool) -> Self {
+        match (int1, int2) {
+            (false, false) => InterruptPin::None,
+            (true, false) => InterruptPin::Int1,
+            (false, true) => InterruptPin::Int2,
+            (true, true) => InterruptPin::Both,
+        }
+    }
+}
+
+/// Routing of the interrupt sources to the INT1 and INT2 pins (INT_MAP register)
+///
+/// Default is no interrupts enabled
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct InterruptMap {
+    /// data ready (DATA_RDY)
+    pub data_ready: InterruptPin,
+    /// FIFO watermark reached (FIFO_FULL)
+    pub fifo_full: InterruptPin,
+    /// FIFO overrun (FIFO_OVR)
+    pub fifo_overrun: InterruptPin,
+    /// activity detected (Activity)
+    pub activity: InterruptPin,
+}
+
+impl InterruptMap {
+    pub fn val(self) -> u8 {
+        let sources = [self.data_ready, self.fifo_full, self.fifo_overrun, self.activity];
+        let mut reg = 0;
+        for (bit, pin) in sources.iter().enumerate() {
+            if pin.val() & InterruptPin::Int1.val() != 0 {
+                reg |= 1 << bit;
+            }
+            if pin.val() & InterruptPin::Int2.val() != 0 {
+                reg |= 1 << (bit + 4);
+            }
+        }
+        reg
+    }
+}
+
+impl From<u8> for InterruptMap {
+    /// Decodes the INT_MAP register
+    fn from(reg: u8) -> Self {
+        let pin = |bit: u8| InterruptPin::from_bits(reg & (1 << bit) != 0, reg & (1 << (bit + 4)) != 0);
+        InterruptMap {
+            data_ready: pin(0),
+            fifo_full: pin(1),
+            fifo_overrun: pin(2),
+            activity: pin(3),
+        }
+    }
+}
+
+/// Polarity of the INT1 and INT2 pins
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InterruptPolarity {
+    /// INT1 and INT2 are active low
+    ActiveLow  = 0,
+    /// INT1 and INT2 are active high
+    ActiveHigh = 1,
+}
+
+impl Default for InterruptPolarity {
+    fn default() -> Self {
+        InterruptPolarity::ActiveLow
+    }
+}
+
+impl InterruptPolarity {
+    pub fn val(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Activity detection settings
+///
+/// Activity is detected when the acceleration on any enabled axis exceeds the
+/// threshold for `count` consecutive samples.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ActivityConfig {
+    /// enable activity detection on the x-axis
+    pub x: bool,
+    /// enable activity detection on the y-axis
+    pub y: bool,
+    /// enable activity detection on the z-axis
+    pub z: bool,
+    /// threshold in mg
+    pub threshold_mg: u32,
+    /// number of consecutive samples above the threshold
+    pub count: u8,
+}
+
+impl ActivityConfig {
+    /// Value of the ACT_EN register
+    pub fn enable_val(self) -> u8 {
+        (self.x as u8) | (self.y as u8) << 1 | (self.z as u8) << 2
+    }
+
+    /// Value of the ACT_THRESH registers for the given range, or `None` if the
+    /// threshold does not fit.
+    ///
+    /// ACT_THRESH is compared against bits 18 to 3 of the acceleration data, so
+    /// one threshold LSB equals 8 data LSB.
+    pub fn threshold_val<R: RangeSetting>(self, range: R) -> Option<u16> {
+        let lsb = self.threshold_mg as u64 * range.lsb_per_g() as u64 / 1000 / 8;
+        if lsb > u16::MAX as u64 {
+            None
+        } else {
+            Some(lsb as u16)
+        }
+    }
+}
+
+/// Per-axis scale calibration applied to the normalized output
+///
+/// Each axis is corrected as `gain * (reading - bias)`, with the reading and bias in g.
+#[cfg(feature = "float")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ScaleCalibration {
+    /// gain per axis, nominally 1.0
+    pub gain: F32x3,
+    /// bias per axis in g, nominally 0.0
+    pub bias: F32x3,
+}
+
+#[cfg(feature = "float")]
+impl ScaleCalibration {
+    /// Applies the calibration to a reading in g
+    pub fn apply(&self, g: F32x3) -> F32x3 {
+        F32x3::new(
+            self.gain.x * (g.x - self.bias.x),
+            self.gain.y * (g.y - self.bias.y),
+            self.gain.z * (g.z - self.bias.z),
+        )
+    }
+}
+
+#[cfg(feature = "float")]
+impl Default for ScaleCalibration {
+    fn default() -> Self {
+        ScaleCalibration {
+            gain: F32x3::new(1.0, 1.0, 1.0),
+            bias: F32x3::new(0.0, 0.0, 0.0),
+        }
+    }
+}
+
+/// Axis aligned with gravity during a zero-g offset calibration
+///
+/// `PlusZ` means the z-axis reads +1 g, e.g. the device lies flat with its top side up.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GravityAxis {
+    PlusX,
+    MinusX,
+    PlusY,
+    MinusY,
+    PlusZ,
+    MinusZ,
+}
+
+const TEMP_CODE_MAX: u16 = 0x0FFF; // 12-bit TEMP2/TEMP1 code
+
+/// Temperature sensor transfer function
+///
+/// Defaults to the nominal datasheet values, 1885 LSB at 25 °C and a slope of -9.05 LSB/°C.
+/// Calibrated units can supply their own values.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TempCalibration {
+    pub(crate) intercept_lsb: u16,
+    pub(crate) slope_centi_lsb: i32,
+}
+
+impl TempCalibration {
+    /// Creates a transfer function from the raw temperature code at 25 °C and the
+    /// slope in 1/100 LSB per °C. Returns `None` for a slope of 0, or an intercept
+    /// above the 12-bit temperature code range.
+    pub fn new(intercept_lsb: u16, slope_centi_lsb: i32) -> Option<Self> {
+        if slope_centi_lsb == 0 || intercept_lsb > TEMP_CODE_MAX {
+            return None;
+        }
+        Some(TempCalibration { intercept_lsb, slope_centi_lsb })
+    }
+
+    /// Raw temperature code at 25 °C
+    pub fn intercept_lsb(&self) -> u16 {
+        self.intercept_lsb
+    }
+
+    /// Slope in 1/100 LSB per °C
+    pub fn slope_centi_lsb(&self) -> i32 {
+        self.slope_centi_lsb
+    }
+
+    /// Converts a raw temperature code to °C
+    #[cfg(feature = "float")]
+    pub fn celsius(&self, raw: u16) -> f32 {
+        25.0 + (raw as i32 - self.intercept_lsb as i32) as f32 * 100.0 / self.slope_centi_lsb as f32
+    }
+
+    /// Converts a raw temperature code to m°C, saturating at the `i32` range
+    pub fn millicelsius(&self, raw: u16) -> i32 {
+        let delta = raw as i64 - self.intercept_lsb as i64;
+        let millicelsius = 25_000 + delta * 100_000 / self.slope_centi_lsb as i64;
+        millicelsius.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+    }
+}
+
+impl Default for TempCalibration {
+    fn default() -> Self {
+        variant::Adxl355::TEMP_CALIBRATION
+    }
+}
+
+/// ADXL35x configuration struct
+///
+/// The `V` parameter selects the part, which determines the [`Range`](Self::range) type.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Config<V: Variant = variant::Adxl355> {
+    pub(crate) range: Option<V::Range>,
     pub(crate) odr: Option<ODR_LPF>,
-    pub(crate) hpf: Option<HPF_CORNER>
+    pub(crate) hpf: Option<HPF_CORNER>,
+    pub(crate) temp_cal: Option<TempCalibration>,
+    pub(crate) fifo_watermark: Option<u8>,
+    pub(crate) int_map: Option<InterruptMap>,
+    pub(crate) int_pol: Option<InterruptPolarity>,
+    pub(crate) activity: Option<ActivityConfig>,
+    pub(crate) sync: Option<SyncMode>,
+    pub(crate) ext_clk: Option<Option<u32>>,
+    pub(crate) temp_off: Option<bool>,
+    pub(crate) drdy_off: Option<bool>,
+    pub(crate) offsets: Option<I32x3>,
+    #[cfg(feature = "float")]
+    pub(crate) scale_cal: Option<ScaleCalibration>,
+    #[cfg(feature = "float")]
+    pub(crate) gravity: Option<f32>
 }
 
-/// ADXL355 configuration struct
 impl Config {
 
     // Creates a new configuration object with default values
     pub fn new() -> Self {
+        Config::default()
+    }
+}
+
+impl<V: Variant> Default for Config<V> {
+    fn default() -> Self {
         Config {
             range: None,
             odr: None,
-            hpf: None
+            hpf: None,
+            temp_cal: None,
+            fifo_watermark: None,
+            int_map: None,
+            int_pol: None,
+            activity: None,
+            sync: None,
+            ext_clk: None,
+            temp_off: None,
+            drdy_off: None,
+            offsets: None,
+            #[cfg(feature = "float")]
+            scale_cal: None,
+            #[cfg(feature = "float")]
+            gravity: None
         }
     }
+}
+
+impl<V: Variant> Config<V> {
 
     /// Sets the range configuration
-    /// Default is 2G
-    pub fn range(&mut self, range: Range) -> &mut Self {
+    /// Default is 2G for the ADXL355 and 10G for the ADXL357
+    ///
+    /// Offsets already stored in the driver are converted to the new range,
+    /// unless this configuration also sets them.
+    pub fn range(&mut self, range: V::Range) -> &mut Self {
         self.range = Some(range);
         self
     }
@@ -157,4 +614,260 @@ impl Config {
         self.hpf = Some(hpf);
         self
     }
+
+    /// Sets the temperature sensor calibration
+    ///
+    /// Default is the nominal datasheet transfer function
+    pub fn temp_calibration(&mut self, cal: TempCalibration) -> &mut Self {
+        self.temp_cal = Some(cal);
+        self
+    }
+
+    /// Sets the number of FIFO entries that sets the FIFO_FULL status bit (1 to 96).
+    /// Each axis takes one entry, so use a multiple of 3 to wait for complete samples.
+    ///
+    /// Default is 96. Out of range values are rejected when the configuration is applied.
+    pub fn fifo_watermark(&mut self, entries: u8) -> &mut Self {
+        self.fifo_watermark = Some(entries);
+        self
+    }
+
+    /// Sets the routing of interrupt sources to the INT1 and INT2 pins
+    ///
+    /// Default is no interrupts enabled
+    pub fn interrupts(&mut self, map: InterruptMap) -> &mut Self {
+        self.int_map = Some(map);
+        self
+    }
+
+    /// Sets the polarity of the INT1 and INT2 pins
+    ///
+    /// Default is active low
+    pub fn interrupt_polarity(&mut self, polarity: InterruptPolarity) -> &mut Self {
+        self.int_pol = Some(polarity);
+        self
+    }
+
+    /// Sets the activity detection settings
+    ///
+    /// Default is activity detection disabled
+    pub fn activity(&mut self, activity: ActivityConfig) -> &mut Self {
+        self.activity = Some(activity);
+        self
+    }
+
+    /// Sets the external synchronization mode
+    ///
+    /// Default is internal synchronization
+    pub fn sync(&mut self, sync: SyncMode) -> &mut Self {
+        self.sync = Some(sync);
+        self
+    }
+
+    /// Enables the external clock on the INT2 pin, running at `hz`.
+    /// The output data rate scales with the ratio to the nominal 1.024 MHz clock.
+    ///
+    /// Default is the internal clock
+    pub fn external_clock(&mut self, hz: u32) -> &mut Self {
+        self.ext_clk = Some(Some(hz));
+        self
+    }
+
+    /// Selects the internal clock, turning off an external clock set before
+    pub fn internal_clock(&mut self) -> &mut Self {
+        self.ext_clk = Some(None);
+        self
+    }
+
+    /// Disables the temperature processing to save power
+    ///
+    /// Default is temperature processing enabled
+    pub fn temp_off(&mut self, off: bool) -> &mut Self {
+        self.temp_off = Some(off);
+        self
+    }
+
+    /// Forces the DRDY output to 0
+    ///
+    /// Default is DRDY enabled
+    pub fn drdy_off(&mut self, off: bool) -> &mut Self {
+        self.drdy_off = Some(off);
+        self
+    }
+
+    /// Sets the offset trim registers, in raw acceleration units
+    ///
+    /// Default is no offset
+    pub fn offsets(&mut self, offsets: I32x3) -> &mut Self {
+        self.offsets = Some(offsets);
+        self
+    }
+
+    /// Sets the per-axis scale calibration applied to the normalized output
+    ///
+    /// Default is the nominal sensitivity without correction
+    #[cfg(feature = "float")]
+    pub fn scale_calibration(&mut self, cal: ScaleCalibration) -> &mut Self {
+        self.scale_cal = Some(cal);
+        self
+    }
+
+    /// Sets the local gravity in m/s² used to convert readings to SI units
+    ///
+    /// Default is standard gravity, 9.80665 m/s²
+    #[cfg(feature = "float")]
+    pub fn gravity(&mut self, gravity: f32) -> &mut Self {
+        self.gravity = Some(gravity);
+        self
+    }
+
+    /// Returns the range setting
+    pub fn get_range(&self) -> Option<V::Range> {
+        self.range
+    }
+
+    /// Returns the output data rate setting
+    pub fn get_odr(&self) -> Option<ODR_LPF> {
+        self.odr
+    }
+
+    /// Returns the high pass filter setting
+    pub fn get_hpf(&self) -> Option<HPF_CORNER> {
+        self.hpf
+    }
+
+    /// Returns the temperature sensor calibration
+    pub fn get_temp_calibration(&self) -> Option<TempCalibration> {
+        self.temp_cal
+    }
+
+    /// Returns the FIFO watermark in entries
+    pub fn get_fifo_watermark(&self) -> Option<u8> {
+        self.fifo_watermark
+    }
+
+    /// Returns the interrupt routing
+    pub fn get_interrupts(&self) -> Option<InterruptMap> {
+        self.int_map
+    }
+
+    /// Returns the interrupt polarity
+    pub fn get_interrupt_polarity(&self) -> Option<InterruptPolarity> {
+        self.int_pol
+    }
+
+    /// Returns the activity detection settings
+    pub fn get_activity(&self) -> Option<ActivityConfig> {
+        self.activity
+    }
+
+    /// Returns the external synchronization mode
+    pub fn get_sync(&self) -> Option<SyncMode> {
+        self.sync
+    }
+
+    /// Returns the clock setting, `Some(None)` if the internal clock is selected
+    pub fn get_external_clock(&self) -> Option<Option<u32>> {
+        self.ext_clk
+    }
+
+    /// Returns the TEMP_OFF setting
+    pub fn get_temp_off(&self) -> Option<bool> {
+        self.temp_off
+    }
+
+    /// Returns the DRDY_OFF setting
+    pub fn get_drdy_off(&self) -> Option<bool> {
+        self.drdy_off
+    }
+
+    /// Returns the offsets in raw acceleration units
+    pub fn get_offsets(&self) -> Option<I32x3> {
+        self.offsets
+    }
+
+    /// Returns the scale calibration
+    #[cfg(feature = "float")]
+    pub fn get_scale_calibration(&self) -> Option<ScaleCalibration> {
+        self.scale_cal
+    }
+
+    /// Returns the local gravity in m/s²
+    #[cfg(feature = "float")]
+    pub fn get_gravity(&self) -> Option<f32> {
+        self.gravity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(threshold_mg: u32) -> ActivityConfig {
+        ActivityConfig { threshold_mg, ..Default::default() }
+    }
+
+    #[test]
+    fn threshold_val_adxl355() {
+        assert_eq!(activity(1000).threshold_val(Range::_2G), Some(32_000));
+        assert_eq!(activity(1000).threshold_val(Range::_8G), Some(8_000));
+        assert_eq!(activity(2047).threshold_val(Range::_2G), Some(65_504));
+        assert_eq!(activity(2048).threshold_val(Range::_2G), None);
+        assert_eq!(activity(2048).threshold_val(Range::_4G), Some(32_768));
+    }
+
+    #[test]
+    fn threshold_val_adxl357() {
+        assert_eq!(activity(1000).threshold_val(Range357::_10G), Some(6_400));
+        assert_eq!(activity(1000).threshold_val(Range357::_40G), Some(1_600));
+        assert_eq!(activity(10_239).threshold_val(Range357::_10G), Some(65_529));
+        assert_eq!(activity(10_240).threshold_val(Range357::_10G), None);
+        assert_eq!(activity(40_000).threshold_val(Range357::_40G), Some(64_000));
+    }
+
+    #[test]
+    fn interrupt_map_encode() {
+        let map = InterruptMap {
+            data_ready: InterruptPin::Int1,
+            fifo_full: InterruptPin::Int2,
+            fifo_overrun: InterruptPin::Both,
+            activity: InterruptPin::None,
+        };
+
+        assert_eq!(map.val(), 0b0110_0101);
+        assert_eq!(InterruptMap::default().val(), 0);
+    }
+
+    #[test]
+    fn interrupt_map_decode() {
+        for reg in 0..=u8::MAX {
+            assert_eq!(InterruptMap::from(reg).val(), reg);
+        }
+    }
+
+    #[test]
+    fn temp_calibration_millicelsius() {
+        let cal = TempCalibration::default();
+
+        assert_eq!(cal.millicelsius(1885), 25_000);
+        assert_eq!(cal.millicelsius(1885 - 905), 125_000);
+        assert_eq!(cal.millicelsius(1885 + 181), 5_000);
+        assert_eq!(TempCalibration::new(0, -905).unwrap().millicelsius(u16::MAX), -7_216_436);
+    }
+
+    #[test]
+    fn temp_calibration_rejects_invalid_values() {
+        assert_eq!(TempCalibration::new(1885, 0), None);
+        assert_eq!(TempCalibration::new(0x1000, -905), None);
+        assert_eq!(TempCalibration::new(0x0FFF, -905).map(|cal| cal.intercept_lsb()), Some(0x0FFF));
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn temp_calibration_celsius() {
+        let cal = TempCalibration::new(1885, -905).unwrap();
+
+        assert!((cal.celsius(1885) - 25.0).abs() < 1e-3);
+        assert!((cal.celsius(1885 - 905) - 125.0).abs() < 1e-3);
+    }
 }
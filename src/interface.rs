@@ -0,0 +1,201 @@
+//! Bus interfaces
+//!
+//! The driver accesses the device registers through an [`Interface`]. The ADXL355
+//! supports SPI with a chip select pin ([`SpiInterface`]) and I2C ([`I2cInterface`]).
+//! On a shared SPI bus, [`SpiDeviceInterface`] takes an embedded-hal 1.0 `SpiDevice`,
+//! which handles chip select and bus locking outside the driver.
+
+use core::convert::Infallible;
+
+use embedded_hal as hal;
+
+use hal::blocking::{i2c, spi};
+use hal::digital::v2::OutputPin;
+
+use embedded_hal_1::spi::{Operation, SpiDevice};
+
+use crate::Error;
+
+pub(crate) const SPI_READ: u8 = 0x01;
+pub(crate) const SPI_WRITE: u8 = 0x00;
+
+pub(crate) const I2C_WRITE_MAX: usize = 6; // longest register block written by the driver
+
+/// Register access over a serial bus
+pub trait Interface {
+    /// Bus error type
+    type BusError;
+    /// Chip select pin error type, [`Infallible`] for buses without one
+    type PinError;
+
+    /// Reads consecutive registers starting at `reg` into `buffer`.
+    ///
+    /// Reading FIFO_DATA does not advance the register address, so `buffer`
+    /// is filled with consecutive FIFO entries instead.
+    fn read(&mut self, reg: u8, buffer: &mut [u8]) -> Result<(), Error<Self::BusError, Self::PinError>>;
+
+    /// Writes `data` to consecutive registers starting at `reg`
+    fn write(&mut self, reg: u8, data: &[u8]) -> Result<(), Error<Self::BusError, Self::PinError>>;
+
+    /// Returns `true` if the I2C_HS bit in RANGE has to be set
+    fn high_speed(&self) -> bool {
+        false
+    }
+}
+
+/// SPI interface with a chip select pin
+pub struct SpiInterface<SPI, CS> {
+    spi: SPI,
+    cs: CS,
+}
+
+impl<SPI, CS> SpiInterface<SPI, CS> {
+    /// Creates an interface from a SPI peripheral and chip select pin
+    pub fn new(spi: SPI, cs: CS) -> Self {
+        SpiInterface { spi, cs }
+    }
+
+    /// Returns the SPI peripheral and chip select pin
+    pub fn release(self) -> (SPI, CS) {
+        (self.spi, self.cs)
+    }
+}
+
+impl<SPI, CS, E, PinError> Interface for SpiInterface<SPI, CS>
+where
+    SPI: spi::Transfer<u8, Error=E> + spi::Write<u8, Error=E>,
+    CS: OutputPin<Error = PinError>
+{
+    type BusError = E;
+    type PinError = PinError;
+
+    fn read(&mut self, reg: u8, buffer: &mut [u8]) -> Result<(), Error<E, PinError>> {
+        self.cs.set_low().map_err(Error::Pin)?;
+        let result = self.spi.write(&[(reg << 1) | SPI_READ])
+            .and_then(|_| self.spi.transfer(buffer).map(|_| ()))
+            .map_err(Error::Bus);
+        self.cs.set_high().map_err(Error::Pin)?;
+        result
+    }
+
+    fn write(&mut self, reg: u8, data: &[u8]) -> Result<(), Error<E, PinError>> {
+        self.cs.set_low().map_err(Error::Pin)?;
+        let result = self.spi.write(&[(reg << 1) | SPI_WRITE])
+            .and_then(|_| self.spi.write(data))
+            .map_err(Error::Bus);
+        self.cs.set_high().map_err(Error::Pin)?;
+        result
+    }
+}
+
+/// SPI device interface, with chip select and bus sharing handled by the `SpiDevice` implementation
+pub struct SpiDeviceInterface<SPI> {
+    pub(crate) spi: SPI,
+}
+
+impl<SPI> SpiDeviceInterface<SPI> {
+    /// Creates an interface from a SPI device
+    pub fn new(spi: SPI) -> Self {
+        SpiDeviceInterface { spi }
+    }
+
+    /// Returns the SPI device
+    pub fn release(self) -> SPI {
+        self.spi
+    }
+}
+
+impl<SPI: SpiDevice> Interface for SpiDeviceInterface<SPI> {
+    type BusError = SPI::Error;
+    type PinError = Infallible;
+
+    fn read(&mut self, reg: u8, buffer: &mut [u8]) -> Result<(), Error<SPI::Error, Infallible>> {
+        let header = [(reg << 1) | SPI_READ];
+        self.spi.transaction(&mut [Operation::Write(&header), Operation::Read(buffer)])
+            .map_err(Error::Bus)
+    }
+
+    fn write(&mut self, reg: u8, data: &[u8]) -> Result<(), Error<SPI::Error, Infallible>> {
+        let header = [(reg << 1) | SPI_WRITE];
+        self.spi.transaction(&mut [Operation::Write(&header), Operation::Write(data)])
+            .map_err(Error::Bus)
+    }
+}
+
+/// I2C device address, selected by the ASEL pin
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum I2cAddress {
+    /// ASEL low, `0x1D`
+    AselLow,
+    /// ASEL high, `0x53`
+    AselHigh,
+}
+
+impl Default for I2cAddress {
+    fn default() -> Self {
+        I2cAddress::AselLow
+    }
+}
+
+impl I2cAddress {
+    /// 7-bit bus address
+    pub fn addr(self) -> u8 {
+        match self {
+            I2cAddress::AselLow => 0x1D,
+            I2cAddress::AselHigh => 0x53,
+        }
+    }
+}
+
+/// I2C interface
+pub struct I2cInterface<I2C> {
+    pub(crate) i2c: I2C,
+    pub(crate) address: I2cAddress,
+    pub(crate) high_speed: bool,
+}
+
+impl<I2C> I2cInterface<I2C> {
+    /// Creates an interface for the device at `address`, in fast mode
+    pub fn new(i2c: I2C, address: I2cAddress) -> Self {
+        I2cInterface { i2c, address, high_speed: false }
+    }
+
+    /// Selects high speed mode (up to 3.4 MHz) by setting the I2C_HS bit in RANGE.
+    ///
+    /// The bit is written when the driver configures the device.
+    pub fn high_speed(mut self, enabled: bool) -> Self {
+        self.high_speed = enabled;
+        self
+    }
+
+    /// Returns the I2C peripheral
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+}
+
+impl<I2C, E> Interface for I2cInterface<I2C>
+where
+    I2C: i2c::Write<Error=E> + i2c::WriteRead<Error=E>
+{
+    type BusError = E;
+    type PinError = Infallible;
+
+    fn read(&mut self, reg: u8, buffer: &mut [u8]) -> Result<(), Error<E, Infallible>> {
+        self.i2c.write_read(self.address.addr(), &[reg], buffer).map_err(Error::Bus)
+    }
+
+    fn write(&mut self, reg: u8, data: &[u8]) -> Result<(), Error<E, Infallible>> {
+        if data.len() > I2C_WRITE_MAX {
+            return Err(Error::InvalidParam);
+        }
+        let mut bytes = [0u8; 1 + I2C_WRITE_MAX];
+        bytes[0] = reg;
+        bytes[1..1 + data.len()].copy_from_slice(data);
+        self.i2c.write(self.address.addr(), &bytes[..1 + data.len()]).map_err(Error::Bus)
+    }
+
+    fn high_speed(&self) -> bool {
+        self.high_speed
+    }
+}
@@ -1,7 +1,7 @@
-//! ADXL355 embedded-hal SPI driver crate
+//! ADXL355 embedded-hal SPI and I2C driver crate
 //!
 //! A platform agnostic driver to interface with the ADXL355 Accelerometer.
-//! This driver uses SPI via [embedded-hal] and implements the [`Accelerometer` trait][trait]
+//! This driver uses SPI or I2C via [embedded-hal] and implements the [`Accelerometer` trait][trait]
 //! from the `accelerometer` crate.
 //!
 //! [embedded-hal]: https://docs.rs/embedded-hal
@@ -13,7 +13,7 @@
 //! Use embedded-hal implementation to get SPI and a GPIO OutputPin for the chip select,
 //! then create the accelerometer handle
 //!
-//! ```
+//! ```ignore
 //!
 //! use adxl355::{Adxl355, Config as ADXLConfig, ODR_LPF, Range, Accelerometer};
 //!
@@ -21,7 +21,7 @@
 //! let mut accelerometer = Adxl355::default(spi, cs)?;
 //!
 //! // start measurements
-//! accelerometer.start();
+//! let mut accelerometer = accelerometer.start().map_err(|(e, _)| e)?;
 //!
 //! // to get 3d accerlation data:
 //! let accel = accelerometer.accel_norm()?;
@@ -36,6 +36,74 @@
 //!                     .range(Range::_2G))?;
 //! ```
 //!
+//! # I2C
+//!
+//! The same API is available over I2C. The address depends on the ASEL pin:
+//!
+//! ```ignore
+//! use adxl355::{Adxl355, Config, I2cAddress, I2cInterface};
+//!
+//! let accelerometer = Adxl355::new_i2c(i2c, I2cAddress::AselLow, &Config::new())?;
+//!
+//! // high speed mode sets the I2C_HS bit in the RANGE register
+//! let interface = I2cInterface::new(i2c, I2cAddress::AselLow).high_speed(true);
+//! let accelerometer = Adxl355::with_interface(interface, &Config::new())?;
+//! ```
+//!
+//! # Shared SPI bus
+//!
+//! To share the SPI bus with other devices, pass an embedded-hal 1.0 `SpiDevice`,
+//! e.g. from `embedded-hal-bus`, which handles chip select and bus locking:
+//!
+//! ```ignore
+//! let accelerometer = Adxl355::new_spi_device(spi_device, &Config::new())?;
+//! ```
+//!
+//! `release` destroys the driver and returns the bus peripheral, and the chip
+//! select pin for a driver created with [`Adxl355::new`]. `release_standby` puts
+//! the device in `Standby mode` first.
+//!
+//! # Operating modes
+//!
+//! A new driver is in the [`Standby`] state, where the configuration can be changed.
+//! [`Adxl355::start`] consumes it and returns a driver in the [`Measuring`] state,
+//! which implements the [`Accelerometer`] and [`RawAccelerometer`] traits.
+//! [`Adxl355::stop`] returns to [`Standby`]. If the mode change fails, both return
+//! the unchanged driver along with the error.
+//!
+//! # ADXL357
+//!
+//! The ADXL357 shares the register map, but has different ranges and sensitivity.
+//! Both parts report the same device ID, so the driver cannot detect which one is
+//! fitted. Use the [`Adxl357`] alias with a [`Config`] for [`variant::Adxl357`]:
+//!
+//! ```ignore
+//! use adxl355::{Adxl357, Config, Range357, variant};
+//!
+//! let mut cfg: Config<variant::Adxl357> = Config::default();
+//! cfg.range(Range357::_20G);
+//! let accelerometer = Adxl357::new(spi, cs, &cfg)?;
+//! ```
+//!
+//! For the ADXL357, [`Adxl355::self_test`] reports the output change per axis, but
+//! no pass/fail result.
+//!
+//! # Features
+//!
+//! - `float` (enabled by default): the [`Accelerometer`] implementation, the other
+//!   `f32` based conversions, the scale calibration and the local gravity setting.
+//!   Disable default features on targets without an FPU and use
+//!   [`Adxl355::accel_micro_g`] and [`Adxl355::read_temperature_milli`] instead.
+//! - `async`: the `asynch` module with a driver on `embedded-hal-async`, for SPI
+//!   devices and I2C buses.
+//!
+//! # Errors
+//!
+//! All fallible methods return [`Error`], which wraps the error types of the bus
+//! and the chip select pin. Over I2C the pin error type is [`Infallible`].
+//! The [`Accelerometer`] and [`RawAccelerometer`] implementations wrap it in an
+//! `accelerometer::Error` with a matching `ErrorKind`.
+//!
 //! # References
 //!
 //! - [Register Map][1]
@@ -53,171 +121,1326 @@
 #![no_std]
 
 mod conf;
+mod interface;
+mod mode;
 mod register;
+mod settings;
+mod status;
+pub mod variant;
+#[cfg(feature = "async")]
+pub mod asynch;
+#[cfg(test)]
+mod mock;
 
+use core::convert::Infallible;
 use core::fmt::Debug;
+use core::marker::PhantomData;
 
 use embedded_hal as hal;
 
-use hal::blocking::spi;
+use hal::blocking::{i2c, spi};
+use hal::blocking::delay::DelayMs;
 use hal::digital::v2::OutputPin;
 
-pub use accelerometer::{Accelerometer, RawAccelerometer, error, Error, vector::{I32x3, F32x3}};
+pub use accelerometer::{Accelerometer, RawAccelerometer, error, vector::{I32x3, F32x3}};
+use accelerometer::error::ErrorKind;
 
 pub use conf::*;
+pub use interface::{Interface, SpiInterface, SpiDeviceInterface, I2cInterface, I2cAddress};
+pub use mode::{Mode, Measuring, Standby};
+pub use status::Status;
+use variant::{RangeSetting, Variant};
 use register::Register;
+use settings::*;
+
+const EXPECTED_DEVICE_ID_AD: u8 = 0xAD;
+const EXPECTED_DEVICE_ID_MST: u8 = 0x1D;
+
+const RESET_CODE: u8 = 0x52;
+const RESET_POLL_INTERVAL_MS: u8 = 1;
+const RESET_POLL_ATTEMPTS: u8 = 10;
+
+const TEMP_READ_ATTEMPTS: u8 = 4;
 
-const SPI_READ: u8 = 0x01;
-const SPI_WRITE: u8 = 0x00;
+const FIFO_X_MARKER: u8 = 0x01;
+const FIFO_EMPTY: u8 = 0x02;
 
-const EXPECTED_DEVICE_ID: u8 = 0xED;
+const SELF_TEST_ST1: u8 = 0x01;
+const SELF_TEST_ST2: u8 = 0x02;
+const SELF_TEST_SAMPLES: u16 = 16;
+const SELF_TEST_SETTLE_SAMPLES: u16 = 4;
 
-const ACCEL_MAX_I20: u32 = 524_287; // = 2^(20-1)-1
+// the slowest ODR (3.906 Hz) has a 256 ms sample period
+const DATA_READY_POLL_INTERVAL_MS: u8 = 1;
+const DATA_READY_POLL_ATTEMPTS: u16 = 1000;
 
 
-/// ADXL355 driver
-pub struct Adxl355<SPI, CS> {
-    spi: SPI,
-    cs: CS,
 
-    // configuration
-    odr: ODR_LPF,
-    hpf: HPF_CORNER,
-    range: Range,
+
+/// ADXL355 driver errors
+#[derive(Debug)]
+pub enum Error<BusE, PinE> {
+    /// SPI or I2C bus error
+    Bus(BusE),
+    /// Chip select pin error
+    Pin(PinE),
+    /// The identification registers read from the chip do not match the expected part
+    WrongDevice(DeviceInfo),
+    /// The device did not respond in time
+    Timeout,
+    /// A configuration value is out of range
+    InvalidParam,
+    /// A register read from the device contains a reserved value
+    InvalidData(u8),
 }
 
+impl<BusE, PinE> Error<BusE, PinE> {
+    /// Returns the matching `accelerometer` error kind
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Bus(_) | Error::Pin(_) => ErrorKind::Bus,
+            Error::WrongDevice(_) | Error::Timeout | Error::InvalidData(_) => ErrorKind::Device,
+            Error::InvalidParam => ErrorKind::Param,
+        }
+    }
+}
 
-impl<SPI, CS, E, PinError> Adxl355<SPI, CS>
+impl<BusE: Debug, PinE: Debug> Error<BusE, PinE> {
+    /// Wraps the error into an `accelerometer::Error` of the matching kind
+    fn into_accel_error(self) -> accelerometer::Error<Self> {
+        accelerometer::Error::new_with_cause(self.kind(), self)
+    }
+}
+
+/// Contents of the identification registers
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DeviceInfo {
+    /// Analog Devices ID, `0xAD`
+    pub ad_id: u8,
+    /// Analog Devices MEMS ID, `0x1D`
+    pub mems_id: u8,
+    /// Device ID, `0xED` for the ADXL355 and ADXL357
+    pub part_id: u8,
+    /// Silicon revision
+    pub revision: u8,
+}
+
+impl DeviceInfo {
+    /// Returns `true` if all ID registers match the values expected for an ADXL355.
+    ///
+    /// The ADXL357 reports the same IDs, so this is also `true` for an ADXL357.
+    pub fn is_adxl355(&self) -> bool {
+        self.is_variant::<variant::Adxl355>()
+    }
+
+    /// Returns `true` if all ID registers match the values expected for the part `V`.
+    ///
+    /// The ADXL355 and ADXL357 report the same IDs, so this cannot tell them apart.
+    pub fn is_variant<V: Variant>(&self) -> bool {
+        self.ad_id == EXPECTED_DEVICE_ID_AD
+            && self.mems_id == EXPECTED_DEVICE_ID_MST
+            && self.part_id == V::DEVICE_ID
+    }
+}
+
+/// Result of [`Adxl355::self_test`]
+#[derive(Copy, Clone, Debug)]
+pub struct SelfTestReport {
+    /// Output change per axis in mg
+    pub delta_mg: I32x3,
+    /// `Some(true)` if the output change of all axes is within the datasheet limits,
+    /// `None` if the driver has no limits for the part, currently the ADXL357
+    pub passed: Option<bool>,
+}
+
+/// Configuration registers whose contents differ from the driver configuration
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConfigDrift {
+    /// OFFSET_X/Y/Z
+    pub offsets: bool,
+    /// ACT_EN, ACT_THRESH and ACT_COUNT
+    pub activity: bool,
+    /// FILTER
+    pub filter: bool,
+    /// FIFO_SAMPLES
+    pub fifo_watermark: bool,
+    /// INT_MAP
+    pub int_map: bool,
+    /// SYNC
+    pub sync: bool,
+    /// RANGE
+    pub range: bool,
+    /// POWER_CTL
+    pub power_ctl: bool,
+}
+
+impl ConfigDrift {
+    /// Returns `true` if any register differs
+    pub fn any(&self) -> bool {
+        self.offsets || self.activity || self.filter || self.fifo_watermark
+            || self.int_map || self.sync || self.range || self.power_ctl
+    }
+}
+
+/// ADXL35x driver
+///
+/// The `IF` parameter is the bus [`Interface`], see [`Adxl355::new`] for SPI,
+/// [`Adxl355::new_spi_device`] for a shared SPI bus and [`Adxl355::new_i2c`] for I2C.
+///
+/// The `V` parameter selects the part, see [`variant`]. Use the [`Adxl357`] alias
+/// for the ADXL357.
+///
+/// The `MODE` parameter tracks whether the device is in `Standby mode` or
+/// `Measurement mode`. Configuration setters are only available in [`Standby`],
+/// sample readings only in [`Measuring`]. The exceptions are [`set_odr`](Self::set_odr),
+/// [`set_hpf`](Self::set_hpf), [`set_range`](Self::set_range) and [`apply`](Self::apply),
+/// which put the device in standby while writing when called in [`Measuring`].
+pub struct Adxl355<IF, MODE = Standby, V: Variant = variant::Adxl355> {
+    interface: IF,
+    settings: Settings<V>,
+    recoveries: u32,
+    mode: PhantomData<MODE>,
+}
+
+/// ADXL357 driver
+pub type Adxl357<IF, MODE = Standby> = Adxl355<IF, MODE, variant::Adxl357>;
+
+
+impl<SPI, CS, E, PinError> Adxl355<SpiInterface<SPI, CS>, Standby>
 where
     SPI: spi::Transfer<u8, Error=E> + spi::Write<u8, Error=E>,
     CS: OutputPin<Error = PinError>
 {
-
-
     /// Creates a new `adxl355` driver from a SPI peripheral with
     /// default configuration.
-    pub fn default(spi:SPI, cs:CS) -> Result<Self, E> {
+    pub fn default(spi:SPI, cs:CS) -> Result<Self, Error<E, PinError>> {
         Adxl355::new(spi, cs, &Config::new())
     }
+}
+
+impl<SPI, CS, E, PinError, V> Adxl355<SpiInterface<SPI, CS>, Standby, V>
+where
+    SPI: spi::Transfer<u8, Error=E> + spi::Write<u8, Error=E>,
+    CS: OutputPin<Error = PinError>,
+    V: Variant
+{
+    /// Takes a config object to initialize the adxl355 driver on a SPI bus.
+    /// The part is selected by the variant of the config object.
+    ///
+    /// Fails with [`Error::WrongDevice`] if the identification registers
+    /// do not match the selected part. The ADXL355 and ADXL357 report the same
+    /// IDs, so the caller has to select the variant fitted on the board; with the
+    /// wrong one, readings are scaled by the wrong sensitivity.
+    pub fn new(spi:SPI, cs:CS, config: &Config<V>) -> Result<Self, Error<E, PinError>> {
+        Adxl355::with_interface(SpiInterface::new(spi, cs), config)
+    }
+}
+
+impl<I2C, E, V> Adxl355<I2cInterface<I2C>, Standby, V>
+where
+    I2C: i2c::Write<Error=E> + i2c::WriteRead<Error=E>,
+    V: Variant
+{
+    /// Takes a config object to initialize the adxl355 driver on an I2C bus,
+    /// in fast mode. Use [`with_interface`](Self::with_interface) with
+    /// [`I2cInterface::high_speed`] for high speed mode.
+    pub fn new_i2c(i2c: I2C, address: I2cAddress, config: &Config<V>) -> Result<Self, Error<E, Infallible>> {
+        Adxl355::with_interface(I2cInterface::new(i2c, address), config)
+    }
+}
+
+impl<SPI, V> Adxl355<SpiDeviceInterface<SPI>, Standby, V>
+where
+    SPI: embedded_hal_1::spi::SpiDevice,
+    V: Variant
+{
+    /// Takes a config object to initialize the adxl355 driver on a shared SPI bus.
+    ///
+    /// Chip select and bus locking are handled by the embedded-hal 1.0 `SpiDevice`
+    /// implementation, e.g. a device from `embedded-hal-bus`.
+    pub fn new_spi_device(spi: SPI, config: &Config<V>) -> Result<Self, Error<SPI::Error, Infallible>> {
+        Adxl355::with_interface(SpiDeviceInterface::new(spi), config)
+    }
+}
+
+impl<SPI, CS, MODE, V: Variant> Adxl355<SpiInterface<SPI, CS>, MODE, V> {
+    /// Destroys the driver and returns the SPI peripheral and chip select pin.
+    ///
+    /// The device is left in its current mode.
+    pub fn release(self) -> (SPI, CS) {
+        self.interface.release()
+    }
+}
+
+impl<SPI, CS, E, PinError, MODE, V> Adxl355<SpiInterface<SPI, CS>, MODE, V>
+where
+    SPI: spi::Transfer<u8, Error=E> + spi::Write<u8, Error=E>,
+    CS: OutputPin<Error = PinError>,
+    MODE: Mode,
+    V: Variant
+{
+    /// Puts the device in `Standby mode`, then destroys the driver and returns the
+    /// SPI peripheral and chip select pin.
+    ///
+    /// If the POWER_CTL write fails, the driver is returned with the error, so the
+    /// peripherals can still be reclaimed with [`release`](Self::release).
+    pub fn release_standby(mut self) -> Result<(SPI, CS), (Error<E, PinError>, Self)> {
+        match self.modify_reg(Register::POWER_CTL.addr(), POWER_CTL_STANDBY, POWER_CTL_STANDBY) {
+            Ok(()) => Ok(self.release()),
+            Err(e) => Err((e, self)),
+        }
+    }
+}
+
+impl<SPI, MODE, V: Variant> Adxl355<SpiDeviceInterface<SPI>, MODE, V> {
+    /// Destroys the driver and returns the SPI device.
+    ///
+    /// The device is left in its current mode.
+    pub fn release(self) -> SPI {
+        self.interface.release()
+    }
+}
+
+impl<I2C, MODE, V: Variant> Adxl355<I2cInterface<I2C>, MODE, V> {
+    /// Destroys the driver and returns the I2C peripheral.
+    ///
+    /// The device is left in its current mode.
+    pub fn release(self) -> I2C {
+        self.interface.release()
+    }
+}
 
-    /// Takes a config object to initialize the adxl355 driver
-    pub fn new(spi:SPI, cs:CS, config: &Config) -> Result<Self, E> {
+impl<IF, E, PinError, V> Adxl355<IF, Standby, V>
+where
+    IF: Interface<BusError = E, PinError = PinError>,
+    V: Variant
+{
+    /// Takes a bus interface and a config object to initialize the adxl355 driver.
+    ///
+    /// Fails with [`Error::WrongDevice`] if the identification registers
+    /// do not match the selected part. The ADXL355 and ADXL357 report the same
+    /// IDs, so the caller has to select the variant fitted on the board; with the
+    /// wrong one, readings are scaled by the wrong sensitivity.
+    pub fn with_interface(interface: IF, config: &Config<V>) -> Result<Self, Error<E, PinError>> {
         let mut adxl355 = Adxl355 {
-            spi,
-            cs,
-            odr: config.odr.unwrap_or_default(),
-            hpf: config.hpf.unwrap_or_default(),
-            range: config.range.unwrap_or_default()
+            interface,
+            settings: Settings::new(config).ok_or(Error::InvalidParam)?,
+            recoveries: 0,
+            mode: PhantomData,
         };
 
+        let info = adxl355.device_info()?;
 
-        let id = adxl355.get_device_id();
+        if !info.is_variant::<V>() {
+            return Err(Error::WrongDevice(info));
+        }
 
-        if id != EXPECTED_DEVICE_ID {
-            // error
+        adxl355.configure()?;
 
+        Ok(adxl355)
+    }
+
+    /// Puts the device in `Measurement mode`, leaving the other POWER_CTL bits untouched.
+    /// The defaut after power up is `Standby mode`.
+    ///
+    /// If the POWER_CTL write fails, the driver is returned with the error.
+    #[allow(clippy::type_complexity)]
+    pub fn start(mut self) -> Result<Adxl355<IF, Measuring, V>, (Error<E, PinError>, Self)> {
+        match self.modify_reg(Register::POWER_CTL.addr(), POWER_CTL_STANDBY, 0) {
+            Ok(()) => Ok(self.into_mode()),
+            Err(e) => Err((e, self)),
         }
+    }
 
-        adxl355.write_reg(Register::FILTER.addr(), (adxl355.hpf.val() << 4) | adxl355.odr.val());
-        adxl355.write_reg(Register::RANGE.addr(), adxl355.range.val());
+    /// Sets the number of FIFO entries (1 to 96) that sets the FIFO_FULL status bit.
+    ///
+    /// Together with [`Status::fifo_full`] this allows waiting until a batch of
+    /// samples is buffered and draining it at once with [`read_fifo`](Self::read_fifo).
+    pub fn set_fifo_watermark(&mut self, entries: u8) -> Result<(), Error<E, PinError>> {
+        if !valid_fifo_watermark(entries) {
+            return Err(Error::InvalidParam);
+        }
+        self.write_reg(Register::FIFO_SAMPLES.addr(), entries)?;
+        self.settings.fifo_watermark = entries;
+        Ok(())
+    }
 
-        Ok(adxl355)
+    /// Routes the interrupt sources to the INT1 and INT2 pins
+    pub fn set_interrupt_map(&mut self, map: InterruptMap) -> Result<(), Error<E, PinError>> {
+        self.write_reg(Register::INT_MAP.addr(), map.val())?;
+        self.settings.int_map = map;
+        Ok(())
+    }
+
+    /// Sets the polarity of the INT1 and INT2 pins
+    pub fn set_interrupt_polarity(&mut self, polarity: InterruptPolarity) -> Result<(), Error<E, PinError>> {
+        let mut settings = self.settings;
+        settings.int_pol = polarity;
+        self.write_reg(Register::RANGE.addr(), settings.range_reg(self.interface.high_speed()))?;
+        self.settings.int_pol = polarity;
+        Ok(())
+    }
+
+    /// Configures activity detection. The threshold is converted to register
+    /// units for the active range.
+    pub fn set_activity(&mut self, activity: ActivityConfig) -> Result<(), Error<E, PinError>> {
+        if activity.threshold_val(self.settings.range).is_none() {
+            return Err(Error::InvalidParam);
+        }
+        let mut settings = self.settings;
+        settings.activity = activity;
+        self.write_activity(&settings)?;
+        self.settings = settings;
+        Ok(())
     }
 
-    /// Puts the device in `Measurement mode`. The defaut after power up is `Standby mode`.
-    pub fn start(&mut self) {
-        self.write_reg(Register::POWER_CTL.addr(), 0);
+    /// Sets the offset trim registers, in the same units as [`RawAccelerometer::accel_raw`].
+    ///
+    /// The offsets are added to the output data by the device. They are rounded to
+    /// the register resolution of 16 LSB.
+    pub fn set_offsets(&mut self, offsets: I32x3) -> Result<(), Error<E, PinError>> {
+        self.store_offsets(offsets)
     }
 
+    /// Sets the offset trim registers, in g for the active range
+    #[cfg(feature = "float")]
+    pub fn set_offsets_g(&mut self, offsets: F32x3) -> Result<(), Error<E, PinError>> {
+        let lsb_per_g = self.settings.range.lsb_per_g() as f32;
+        self.set_offsets(I32x3::new(
+            (offsets.x * lsb_per_g) as i32,
+            (offsets.y * lsb_per_g) as i32,
+            (offsets.z * lsb_per_g) as i32,
+        ))
+    }
+}
+
+impl<IF, E, PinError, V> Adxl355<IF, Measuring, V>
+where
+    IF: Interface<BusError = E, PinError = PinError>,
+    V: Variant
+{
+    /// Puts the device in `Standby mode`, leaving the other POWER_CTL bits untouched.
+    ///
+    /// If the POWER_CTL write fails, the driver is returned with the error.
+    #[allow(clippy::type_complexity)]
+    pub fn stop(mut self) -> Result<Adxl355<IF, Standby, V>, (Error<E, PinError>, Self)> {
+        match self.modify_reg(Register::POWER_CTL.addr(), POWER_CTL_STANDBY, POWER_CTL_STANDBY) {
+            Ok(()) => Ok(self.into_mode()),
+            Err(e) => Err((e, self)),
+        }
+    }
+
+    /// Calibrates the zero-g offset while the device is at rest in a known orientation.
+    ///
+    /// Clears the offset registers, averages `samples` readings, and writes the
+    /// correction that brings the axis in `gravity_axis` to ±1 g and the other axes
+    /// to 0 g. Returns the offsets written, in raw units.
+    ///
+    /// Returns [`Error::Timeout`] if no new sample becomes ready within a second,
+    /// in which case the offset registers are left cleared.
+    pub fn calibrate_zero_g<D: DelayMs<u8>>(
+        &mut self,
+        samples: u16,
+        gravity_axis: GravityAxis,
+        delay: &mut D,
+    ) -> Result<I32x3, Error<E, PinError>> {
+        if samples == 0 {
+            return Err(Error::InvalidParam);
+        }
+
+        self.store_offsets(I32x3::new(0, 0, 0))?;
+        // discard the sample that was converted with the old offsets
+        self.read_accel()?;
+
+        let mean = self.average(samples, delay)?;
+
+        let one_g = self.settings.range.lsb_per_g() as i64;
+        let mut expected = [0i64; 3];
+        match gravity_axis {
+            GravityAxis::PlusX => expected[0] = one_g,
+            GravityAxis::MinusX => expected[0] = -one_g,
+            GravityAxis::PlusY => expected[1] = one_g,
+            GravityAxis::MinusY => expected[1] = -one_g,
+            GravityAxis::PlusZ => expected[2] = one_g,
+            GravityAxis::MinusZ => expected[2] = -one_g,
+        }
+
+        let offsets = I32x3::new(
+            (expected[0] - mean.x as i64) as i32,
+            (expected[1] - mean.y as i64) as i32,
+            (expected[2] - mean.z as i64) as i32,
+        );
+        self.store_offsets(offsets)?;
+        Ok(offsets)
+    }
+
+    /// Gets the acceleration in µg, using integer math only.
+    ///
+    /// Scales the raw reading exactly by the nominal sensitivity of the active
+    /// range, e.g. 3.90625 µg/LSB at ±2 g.
+    pub fn accel_micro_g(&mut self) -> Result<I32x3, Error<E, PinError>> {
+        let raw = self.read_accel()?;
+        Ok(self.settings.micro_g(raw))
+    }
+
+    /// Gets the acceleration in m/s², using the configured local gravity
+    #[cfg(feature = "float")]
+    pub fn accel_si(&mut self) -> Result<F32x3, Error<E, PinError>> {
+        let g = self.read_norm()?;
+        Ok(F32x3::new(g.x * self.settings.gravity, g.y * self.settings.gravity, g.z * self.settings.gravity))
+    }
+
+    /// Reads the acceleration in g, scaled by the nominal sensitivity of the
+    /// active range and corrected by the scale calibration
+    #[cfg(feature = "float")]
+    fn read_norm(&mut self) -> Result<F32x3, Error<E, PinError>> {
+        let raw = self.read_accel()?;
+        Ok(self.settings.norm(raw))
+    }
+
+    /// Runs the built-in self test.
+    ///
+    /// Averages readings with the self test force off and on. The difference per
+    /// axis is checked against the datasheet limits. The device must be at rest
+    /// during the test.
+    ///
+    /// SELF_TEST is cleared again whenever the test stops, also when a read fails
+    /// or [`Error::Timeout`] is returned while the self test force is applied.
+    ///
+    /// The test runs with the high pass filter off, so it does not remove the
+    /// output change, and at the widest range, so gravity plus the output change
+    /// does not clip. FILTER and RANGE are restored afterwards, also on error.
+    pub fn self_test<D: DelayMs<u8>>(&mut self, delay: &mut D) -> Result<SelfTestReport, Error<E, PinError>> {
+        let mut test = self.settings;
+        test.hpf = HPF_CORNER::NONE;
+        test.range = V::Range::WIDEST;
+
+        let report = self.reconfigure(|adxl355| adxl355.write_filter_range(&test))
+            .and_then(|_| self.measure_self_test(test.range, delay));
+        let settings = self.settings;
+        let restored = self.reconfigure(|adxl355| adxl355.write_filter_range(&settings));
+        let report = report?;
+        restored?;
+        Ok(report)
+    }
+
+    /// Measures the self test output change at `range`
+    fn measure_self_test<D: DelayMs<u8>>(
+        &mut self,
+        range: V::Range,
+        delay: &mut D,
+    ) -> Result<SelfTestReport, Error<E, PinError>> {
+        self.settle(delay)?;
+        let off = self.average(SELF_TEST_SAMPLES, delay)?;
+
+        let on = self.write_reg(Register::SELF_TEST.addr(), SELF_TEST_ST1 | SELF_TEST_ST2)
+            .and_then(|_| self.settle(delay))
+            .and_then(|_| self.average(SELF_TEST_SAMPLES, delay));
+        let cleared = self.write_reg(Register::SELF_TEST.addr(), 0);
+        let on = on?;
+        cleared?;
+
+        let lsb_per_g = range.lsb_per_g() as i64;
+        let to_mg = |on: i32, off: i32| ((on - off) as i64 * 1000 / lsb_per_g) as i32;
+        let delta_mg = I32x3::new(to_mg(on.x, off.x), to_mg(on.y, off.y), to_mg(on.z, off.z));
+
+        Ok(SelfTestReport {
+            delta_mg,
+            passed: match (V::SELF_TEST_LIMITS_XY_MG, V::SELF_TEST_LIMITS_Z_MG) {
+                (Some(xy), Some(z)) => Some(
+                    xy.contains(&delta_mg.x) && xy.contains(&delta_mg.y) && z.contains(&delta_mg.z)
+                ),
+                _ => None,
+            },
+        })
+    }
+
+    /// Averages `samples` new readings, waiting for DATA_RDY before each one
+    fn average<D: DelayMs<u8>>(&mut self, samples: u16, delay: &mut D) -> Result<I32x3, Error<E, PinError>> {
+        let mut sum = [0i64; 3];
+        for _ in 0..samples {
+            self.wait_data_ready(delay)?;
+            let sample = self.read_accel()?;
+            sum[0] += sample.x as i64;
+            sum[1] += sample.y as i64;
+            sum[2] += sample.z as i64;
+        }
+        let mean = |axis: usize| (sum[axis] / samples as i64) as i32;
+        Ok(I32x3::new(mean(0), mean(1), mean(2)))
+    }
+
+    /// Discards readings while the output settles after a change
+    fn settle<D: DelayMs<u8>>(&mut self, delay: &mut D) -> Result<(), Error<E, PinError>> {
+        self.average(SELF_TEST_SETTLE_SAMPLES, delay).map(|_| ())
+    }
+
+    /// Polls DATA_RDY, returning [`Error::Timeout`] if no sample becomes ready
+    fn wait_data_ready<D: DelayMs<u8>>(&mut self, delay: &mut D) -> Result<(), Error<E, PinError>> {
+        for _ in 0..DATA_READY_POLL_ATTEMPTS {
+            if self.status()?.data_ready() {
+                return Ok(());
+            }
+            delay.delay_ms(DATA_READY_POLL_INTERVAL_MS);
+        }
+        Err(Error::Timeout)
+    }
+}
+
+impl<IF, E, PinError, MODE, V> Adxl355<IF, MODE, V>
+where
+    IF: Interface<BusError = E, PinError = PinError>,
+    MODE: Mode,
+    V: Variant
+{
+    /// Sets the output data rate and low pass filter
+    ///
+    /// In the `Measuring` state the device is put in standby while FILTER is written.
+    pub fn set_odr(&mut self, odr: ODR_LPF) -> Result<(), Error<E, PinError>> {
+        self.reconfigure(|adxl355| {
+            adxl355.write_reg(Register::FILTER.addr(), (adxl355.settings.hpf.val() << 4) | odr.val())?;
+            adxl355.settings.odr = odr;
+            Ok(())
+        })
+    }
+
+    /// Sets the high pass filter corner frequency
+    ///
+    /// In the `Measuring` state the device is put in standby while FILTER is written.
+    pub fn set_hpf(&mut self, hpf: HPF_CORNER) -> Result<(), Error<E, PinError>> {
+        self.reconfigure(|adxl355| {
+            adxl355.write_reg(Register::FILTER.addr(), (hpf.val() << 4) | adxl355.settings.odr.val())?;
+            adxl355.settings.hpf = hpf;
+            Ok(())
+        })
+    }
+
+    /// Sets the measurement range. The activity threshold and the offsets are
+    /// converted to the new range, so they keep the same value in g.
+    ///
+    /// Returns [`Error::InvalidParam`] if the threshold or an offset does not fit
+    /// the registers at the new range.
+    ///
+    /// In the `Measuring` state the device is put in standby while the registers are written.
+    pub fn set_range(&mut self, range: V::Range) -> Result<(), Error<E, PinError>> {
+        let mut config = Config::<V>::default();
+        config.range(range);
+        let settings = self.settings.with_config(&config).ok_or(Error::InvalidParam)?;
+        self.reconfigure(|adxl355| {
+            adxl355.write_reg(Register::RANGE.addr(), settings.range_reg(adxl355.interface.high_speed()))?;
+            adxl355.write_activity(&settings)?;
+            adxl355.write_offsets(&settings)?;
+            adxl355.settings = settings;
+            Ok(())
+        })
+    }
+
+    /// Applies the values set in `config`, leaving the other settings unchanged.
+    ///
+    /// In the `Measuring` state the device is put in standby while the registers are written.
+    /// The driver settings are only updated once all registers are written.
+    pub fn apply(&mut self, config: &Config<V>) -> Result<(), Error<E, PinError>> {
+        let settings = self.settings.with_config(config).ok_or(Error::InvalidParam)?;
+        self.reconfigure(|adxl355| {
+            adxl355.write_settings(&settings)?;
+            adxl355.settings = settings;
+            Ok(())
+        })
+    }
+
+    /// Runs `f` with the device in `Standby mode`, resuming measurements afterwards
+    /// if the driver is in the `Measuring` state
+    fn reconfigure<F>(&mut self, f: F) -> Result<(), Error<E, PinError>>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Error<E, PinError>>
+    {
+        if MODE::MEASURING {
+            self.modify_reg(Register::POWER_CTL.addr(), POWER_CTL_STANDBY, POWER_CTL_STANDBY)?;
+        }
+        let result = f(self);
+        if MODE::MEASURING {
+            self.modify_reg(Register::POWER_CTL.addr(), POWER_CTL_STANDBY, 0)?;
+        }
+        result
+    }
+
+    /// Reads the configuration registers back from the device.
+    ///
+    /// Decodes FILTER, RANGE, SYNC, INT_MAP, POWER_CTL, FIFO_SAMPLES, the activity
+    /// detection and the offset registers. The temperature calibration is not
+    /// stored on the device and is left unset. The external clock frequency is
+    /// taken from the driver configuration, or the nominal 1.024 MHz if unknown.
+    pub fn read_config(&mut self) -> Result<Config<V>, Error<E, PinError>> {
+        let regs = self.read_config_regs()?;
+        self.settings.decode(&regs).map_err(Error::InvalidData)
+    }
+
+    /// Compares the configuration registers of the device with the driver configuration.
+    ///
+    /// A brown-out can reset the device to its defaults while the MCU keeps running;
+    /// this shows up as drift in one or more registers.
+    pub fn verify_config(&mut self) -> Result<ConfigDrift, Error<E, PinError>> {
+        let actual = self.read_config_regs()?;
+        let expected = self.config_regs();
+        let differs = |first: Register, last: Register| {
+            let (first, last) = (config_index(first), config_index(last));
+            actual[first..=last] != expected[first..=last]
+        };
+
+        Ok(ConfigDrift {
+            offsets: differs(Register::OFFSET_X_H, Register::OFFSET_Z_L),
+            activity: differs(Register::ACT_EN, Register::ACT_COUNT),
+            filter: differs(Register::FILTER, Register::FILTER),
+            fifo_watermark: differs(Register::FIFO_SAMPLES, Register::FIFO_SAMPLES),
+            int_map: differs(Register::INT_MAP, Register::INT_MAP),
+            sync: differs(Register::SYNC, Register::SYNC),
+            range: differs(Register::RANGE, Register::RANGE),
+            power_ctl: differs(Register::POWER_CTL, Register::POWER_CTL),
+        })
+    }
+
+    /// Verifies the device configuration and rewrites it if any register differs.
+    ///
+    /// Returns the drift that was found. Every rewrite increments the counter
+    /// returned by [`recovery_count`](Self::recovery_count).
+    ///
+    /// In the `Measuring` state the device is put in standby while the registers are written.
+    pub fn ensure_config(&mut self) -> Result<ConfigDrift, Error<E, PinError>> {
+        let drift = self.verify_config()?;
+        if drift.any() {
+            self.reconfigure(|adxl355| adxl355.configure())?;
+            self.recoveries = self.recoveries.wrapping_add(1);
+        }
+        Ok(drift)
+    }
+
+    /// Number of times [`ensure_config`](Self::ensure_config) had to rewrite the configuration
+    pub fn recovery_count(&self) -> u32 {
+        self.recoveries
+    }
+
+    /// Expected contents of the registers from OFFSET_X_H up to POWER_CTL
+    fn config_regs(&self) -> [u8; CONFIG_REGS] {
+        self.settings.config_regs(MODE::MEASURING, self.interface.high_speed())
+    }
+
+    /// Reads the registers from OFFSET_X_H up to POWER_CTL in one transaction
+    fn read_config_regs(&mut self) -> Result<[u8; CONFIG_REGS], Error<E, PinError>> {
+        let mut regs = [0u8; CONFIG_REGS];
+        self.read_reg(Register::OFFSET_X_H.addr(), &mut regs)?;
+        Ok(regs)
+    }
+
+    /// Reads the operating mode from the POWER_CTL register
+    pub fn power_state(&mut self) -> Result<PowerState, Error<E, PinError>> {
+        let mut output = [0u8];
+        self.read_reg(Register::POWER_CTL.addr(), &mut output)?;
+        if output[0] & POWER_CTL_STANDBY != 0 {
+            Ok(PowerState::Standby)
+        } else {
+            Ok(PowerState::Measurement)
+        }
+    }
+
+    /// Performs a software reset and restores the driver configuration.
+    ///
+    /// After writing the reset code, NVM_BUSY in STATUS is polled until the device
+    /// has reloaded its trim values from NVM, or [`Error::Timeout`] is returned.
+    /// The stored configuration is then rewritten and POWER_CTL is restored, so a
+    /// driver in the `Measuring` state enters `Measurement mode` again.
+    pub fn reset<D: DelayMs<u8>>(&mut self, delay: &mut D) -> Result<(), Error<E, PinError>> {
+        self.write_reg(Register::RESET.addr(), RESET_CODE)?;
+
+        let mut attempts = 0;
+        loop {
+            delay.delay_ms(RESET_POLL_INTERVAL_MS);
+            if !self.status()?.nvm_busy() {
+                break;
+            }
+            attempts += 1;
+            if attempts >= RESET_POLL_ATTEMPTS {
+                return Err(Error::Timeout);
+            }
+        }
+
+        self.configure()
+    }
 
     /// Returns the raw contents of the temperature registers
-    pub fn read_temp_raw(&mut self) -> u16 {
+    pub fn read_temp_raw(&mut self) -> Result<u16, Error<E, PinError>> {
+
+        let mut bytes = [0u8; 2];
+        self.read_reg(Register::TEMP2.addr(), &mut bytes)?;
+
+        let temp_h = ((bytes[0] & 0x0F) as u16) << 8;
+        let temp_l = (bytes[1] as u16) & 0x00FF;
+
+        Ok(temp_h | temp_l)
+    }
+
+    /// Returns the temperature in °C
+    #[cfg(feature = "float")]
+    pub fn read_temperature(&mut self) -> Result<f32, Error<E, PinError>> {
+        let raw = self.read_temp_stable()?;
+        Ok(self.settings.temp_cal.celsius(raw))
+    }
+
+    /// Returns the temperature in m°C, without using floating point math
+    pub fn read_temperature_milli(&mut self) -> Result<i32, Error<E, PinError>> {
+        let raw = self.read_temp_stable()?;
+        Ok(self.settings.temp_cal.millicelsius(raw))
+    }
 
-        let mut bytes = [(Register::TEMP2.addr() << 1)  | SPI_READ, 0, 0];
-        self.read(&mut bytes);
+    /// Overrides the temperature sensor calibration
+    pub fn set_temp_calibration(&mut self, cal: TempCalibration) {
+        self.settings.temp_cal = cal;
+    }
+
+    /// Overrides the per-axis scale calibration of the normalized output
+    #[cfg(feature = "float")]
+    pub fn set_scale_calibration(&mut self, cal: ScaleCalibration) {
+        self.settings.scale_cal = cal;
+    }
 
-        let temp_h = ((bytes[1] & 0x0F) as u16) << 8;
-        let temp_l = (bytes[2] as u16) & 0x00FF;
+    /// Sets the local gravity in m/s² used by [`accel_si`](Adxl355::accel_si)
+    #[cfg(feature = "float")]
+    pub fn set_gravity(&mut self, gravity: f32) {
+        self.settings.gravity = gravity;
+    }
+
+    /// Reads the temperature registers until two consecutive reads agree,
+    /// so TEMP2 and TEMP1 are guaranteed to belong to the same conversion.
+    fn read_temp_stable(&mut self) -> Result<u16, Error<E, PinError>> {
+        let mut previous = self.read_temp_raw()?;
+        for _ in 0..TEMP_READ_ATTEMPTS {
+            let current = self.read_temp_raw()?;
+            if current == previous {
+                return Ok(current);
+            }
+            previous = current;
+        }
+        Err(Error::Timeout)
+    }
+
+    /// Reads the STATUS register
+    ///
+    /// Use [`Status::data_ready`] to poll for new samples on boards where the
+    /// DRDY pin is not connected.
+    pub fn status(&mut self) -> Result<Status, Error<E, PinError>> {
+        let mut output = [0u8];
+        self.read_reg(Register::STATUS.addr(), &mut output)?;
+        Ok(Status::from_bits_truncate(output[0]))
+    }
+
+    /// Returns `true` if activity was detected since the last STATUS read.
+    ///
+    /// Reading the STATUS register clears the flag.
+    pub fn read_activity(&mut self) -> Result<bool, Error<E, PinError>> {
+        Ok(self.status()?.activity())
+    }
 
-        temp_h | temp_l
+    /// Returns the number of valid entries in the FIFO. Each axis counts as one entry.
+    pub fn fifo_entries(&mut self) -> Result<u8, Error<E, PinError>> {
+        let mut output = [0u8];
+        self.read_reg(Register::FIFO_ENTRIES.addr(), &mut output)?;
+        Ok(output[0] & 0x7F)
+    }
+
+    /// Drains complete x, y, z samples from the FIFO into `samples`.
+    ///
+    /// All samples that fit in `samples` are read in a single bus transaction.
+    /// If an earlier read left the FIFO in the middle of a sample, the entries up
+    /// to the next x-axis marker are dropped and the split sample is completed with
+    /// a second, short read. Returns the number of samples written.
+    pub fn read_fifo(&mut self, samples: &mut [I32x3]) -> Result<usize, Error<E, PinError>> {
+        let available = self.fifo_entries()? as usize / 3;
+        let count = available.min(samples.len());
+        if count == 0 {
+            return Ok(0);
+        }
+
+        let mut bytes = [0u8; FIFO_CAPACITY * 3];
+        let entries = &mut bytes[..count * 9];
+        self.read_reg(Register::FIFO_DATA.addr(), entries)?;
+
+        let (mut n, partial) = decode_fifo(entries, samples);
+        if !partial.is_empty() {
+            // read the remaining entries of the last sample to realign the FIFO
+            let mut chunk = [0u8; 9];
+            chunk[..partial.len()].copy_from_slice(partial);
+
+            self.read_reg(Register::FIFO_DATA.addr(), &mut chunk[partial.len()..])?;
+
+            if let Some(sample) = fifo_sample(&chunk) {
+                samples[n] = sample;
+                n += 1;
+            }
+        }
+
+        Ok(n)
     }
 
     /// Get the device ID
-    pub fn get_device_id(&mut self) -> u8 {
+    pub fn get_device_id(&mut self) -> Result<u8, Error<E, PinError>> {
         let reg = Register::DEVID.addr();
         let mut output = [1u8];
-        self.read_reg(reg, &mut output);
-        output[0]
+        self.read_reg(reg, &mut output)?;
+        Ok(output[0])
     }
 
-    fn write_reg(&mut self, reg: u8, value: u8) {
-        let mut bytes = [(reg << 1)  | SPI_WRITE, value];
-        self.cs.set_low().ok();
-        self.spi.write(&mut bytes).ok();
-        self.cs.set_high().ok();
+    /// Reads the identification registers `DEVID_AD`, `DEVID_MST`, `DEVID` and `REVID`
+    pub fn device_info(&mut self) -> Result<DeviceInfo, Error<E, PinError>> {
+        let mut bytes = [0u8; 4];
+        self.read_reg(Register::DEVID_AD.addr(), &mut bytes)?;
+
+        Ok(DeviceInfo {
+            ad_id: bytes[0],
+            mems_id: bytes[1],
+            part_id: bytes[2],
+            revision: bytes[3],
+        })
     }
 
-    fn read_reg(&mut self, reg: u8, buffer: &mut [u8]) {
-        let mut bytes = [(reg << 1)  | SPI_READ, 0];
-        self.cs.set_low().ok();
-        self.spi.transfer(&mut bytes).ok();
-        self.cs.set_high().ok();
-        buffer[0] = bytes[1];
+    fn configure(&mut self) -> Result<(), Error<E, PinError>> {
+        let settings = self.settings;
+        self.write_settings(&settings)
     }
 
-    fn read(&mut self, bytes: &mut [u8]) {
-        self.cs.set_low().ok();
-        self.spi.transfer(bytes).ok();
-        self.cs.set_high().ok();
+    /// Writes all configuration registers from `settings`
+    fn write_settings(&mut self, settings: &Settings<V>) -> Result<(), Error<E, PinError>> {
+        for &(first, last) in CONFIG_SEQUENCE.iter() {
+            self.write_config(settings, first, last)?;
+        }
+        Ok(())
+    }
+
+    fn store_offsets(&mut self, offsets: I32x3) -> Result<(), Error<E, PinError>> {
+        let mut settings = self.settings;
+        settings.offsets = offset_regs(offsets).ok_or(Error::InvalidParam)?;
+        self.write_offsets(&settings)?;
+        self.settings = settings;
+        Ok(())
+    }
+
+    fn write_offsets(&mut self, settings: &Settings<V>) -> Result<(), Error<E, PinError>> {
+        self.write_config(settings, Register::OFFSET_X_H, Register::OFFSET_Z_L)
+    }
+
+    fn write_activity(&mut self, settings: &Settings<V>) -> Result<(), Error<E, PinError>> {
+        self.write_config(settings, Register::ACT_EN, Register::ACT_COUNT)
+    }
+
+    fn write_filter_range(&mut self, settings: &Settings<V>) -> Result<(), Error<E, PinError>> {
+        self.write_config(settings, Register::FILTER, Register::FILTER)?;
+        self.write_config(settings, Register::RANGE, Register::RANGE)
+    }
+
+    /// Writes the registers from `first` up to `last` from `settings`
+    fn write_config(&mut self, settings: &Settings<V>, first: Register, last: Register) -> Result<(), Error<E, PinError>> {
+        let regs = settings.config_regs(MODE::MEASURING, self.interface.high_speed());
+        self.interface.write(first.addr(), &regs[config_index(first)..=config_index(last)])
     }
+
+    fn into_mode<M: Mode>(self) -> Adxl355<IF, M, V> {
+        Adxl355 {
+            interface: self.interface,
+            settings: self.settings,
+            recoveries: self.recoveries,
+            mode: PhantomData,
+        }
+    }
+
+    /// Read-modify-write of the bits in `mask`
+    fn modify_reg(&mut self, reg: u8, mask: u8, value: u8) -> Result<(), Error<E, PinError>> {
+        let mut output = [0u8];
+        self.read_reg(reg, &mut output)?;
+        self.write_reg(reg, (output[0] & !mask) | (value & mask))
+    }
+
+    fn write_reg(&mut self, reg: u8, value: u8) -> Result<(), Error<E, PinError>> {
+        self.interface.write(reg, &[value])
+    }
+
+    fn read_accel(&mut self) -> Result<I32x3, Error<E, PinError>> {
+        let mut bytes = [0u8; 9];
+        self.read_reg(Register::XDATA3.addr(), &mut bytes)?;
+
+        let x = parse_sample(&bytes[0..3]);
+        let y = parse_sample(&bytes[3..6]);
+        let z = parse_sample(&bytes[6..9]);
+
+        Ok(I32x3::new(x, y, z))
+    }
+
+    fn read_reg(&mut self, reg: u8, buffer: &mut [u8]) -> Result<(), Error<E, PinError>> {
+        self.interface.read(reg, buffer)
+    }
+}
+
+/// Combines 3 bytes into one i32 value, right-shifted with sign-extend to 20-bit
+fn parse_sample(bytes: &[u8]) -> i32 {
+    (((bytes[0] as i32) << 24) | ((bytes[1] as i32) << 16) | (((bytes[2] & 0xF0) as i32) << 8)) >> 12
+}
+
+/// Decodes the complete samples in `entries`, read from FIFO_DATA, into `samples`.
+///
+/// Entries before the first x-axis marker belong to a sample that was partially
+/// read before and are skipped. Returns the number of samples written and the
+/// entries of a split sample at the end, which is empty if there is none.
+fn decode_fifo<'a>(entries: &'a [u8], samples: &mut [I32x3]) -> (usize, &'a [u8]) {
+    let offset = match (0..3).find(|i| entries[i * 3 + 2] & FIFO_X_MARKER != 0) {
+        Some(offset) => offset,
+        None => return (0, &[]),
+    };
+
+    let mut n = 0;
+    for chunk in entries[offset * 3..].chunks_exact(9) {
+        match fifo_sample(chunk) {
+            Some(sample) => samples[n] = sample,
+            None => return (n, &[]),
+        }
+        n += 1;
+    }
+
+    let split = if offset > 0 { (3 - offset) * 3 } else { 0 };
+    (n, &entries[entries.len() - split..])
+}
+
+/// Decodes three FIFO entries into a sample, if they form a complete x, y, z set
+fn fifo_sample(chunk: &[u8]) -> Option<I32x3> {
+    if chunk[2] & FIFO_X_MARKER == 0 || chunk.chunks_exact(3).any(|entry| entry[2] & FIFO_EMPTY != 0) {
+        return None;
+    }
+    Some(I32x3::new(parse_sample(&chunk[0..3]), parse_sample(&chunk[3..6]), parse_sample(&chunk[6..9])))
 }
 
-impl<SPI, CS, E, EO> RawAccelerometer<I32x3> for Adxl355<SPI, CS>
+impl<IF, E, PinError, V> RawAccelerometer<I32x3> for Adxl355<IF, Measuring, V>
 where
-    SPI: spi::Transfer<u8, Error=E> + spi::Write<u8, Error=E>,
-    CS: OutputPin<Error = EO>,
-    E: Debug
+    IF: Interface<BusError = E, PinError = PinError>,
+    E: Debug,
+    PinError: Debug,
+    V: Variant
 {
-    type Error = E;
+    type Error = Error<E, PinError>;
 
     /// Gets acceleration vector reading from the accelerometer
     /// Returns a 3D vector with x,y,z, fields in a Result
-    fn accel_raw(&mut self) -> Result<I32x3, Error<E>> {
-        let mut bytes = [0u8; 9+1];
-        bytes[0] = (Register::XDATA3.addr() << 1)  | SPI_READ;
-        self.read(&mut bytes);
-
-        // combine 3 bytes into one i32 value
-        // right-shift with sign-extend to 20-bit
-        let x = ((((bytes[1] as i32) << 24) | ((bytes[2] as i32) << 16) | ((bytes[3] & 0xF0) as i32) << 8)) >> 12;
-        let y = ((((bytes[4] as i32) << 24) | ((bytes[5] as i32) << 16) | ((bytes[6] & 0xF0) as i32) << 8)) >> 12;
-        let z = ((((bytes[7] as i32) << 24) | ((bytes[8] as i32) << 16) | ((bytes[9] & 0xF0) as i32) << 8)) >> 12;
-
-        Ok(I32x3::new(x, y, z))
+    fn accel_raw(&mut self) -> Result<I32x3, accelerometer::Error<Self::Error>> {
+        self.read_accel().map_err(Error::into_accel_error)
     }
 
 }
 
-impl<SPI, CS, E, PinError> Accelerometer for Adxl355<SPI, CS>
+#[cfg(feature = "float")]
+impl<IF, E, PinError, V> Accelerometer for Adxl355<IF, Measuring, V>
 where
-    SPI: spi::Transfer<u8, Error=E> + spi::Write<u8, Error=E>,
-    CS: OutputPin<Error = PinError>,
-    E: Debug
+    IF: Interface<BusError = E, PinError = PinError>,
+    E: Debug,
+    PinError: Debug,
+    V: Variant
 {
-    type Error = E;
+    type Error = Error<E, PinError>;
+
+    fn sample_rate(&mut self) -> Result<f32, accelerometer::Error<Self::Error>> {
+        Ok(self.settings.sample_rate())
+    }
+
+    /// Gets the acceleration in g, scaled by the nominal sensitivity of the
+    /// active range and corrected by the scale calibration
+    fn accel_norm(&mut self) -> Result<F32x3, accelerometer::Error<Self::Error>> {
+        self.read_norm().map_err(Error::into_accel_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::mock::{sample_bytes, MockDelay, MockInterface};
+    use std::vec;
+
+    fn standby(config: &Config) -> Adxl355<MockInterface> {
+        Adxl355::with_interface(MockInterface::new(), config).unwrap()
+    }
+
+    /// Writes FIFO entries, as value and marker bits, to `bytes`
+    fn fifo(entries: &[(i32, u8)], bytes: &mut [u8]) {
+        for (entry, &(value, marker)) in bytes.chunks_exact_mut(3).zip(entries.iter()) {
+            entry.copy_from_slice(&sample_bytes(value));
+            entry[2] |= marker;
+        }
+    }
+
+    #[test]
+    fn decode_fifo_aligned() {
+        let mut entries = [0u8; 18];
+        fifo(&[
+            (1, FIFO_X_MARKER), (-2, 0), (3, 0),
+            (-524_288, FIFO_X_MARKER), (524_287, 0), (0, 0),
+        ], &mut entries);
+        let mut samples = [I32x3::new(0, 0, 0); 2];
+
+        let (n, partial) = decode_fifo(&entries, &mut samples);
+
+        assert_eq!(n, 2);
+        assert!(partial.is_empty());
+        assert_eq!(samples[0], I32x3::new(1, -2, 3));
+        assert_eq!(samples[1], I32x3::new(-524_288, 524_287, 0));
+    }
+
+    #[test]
+    fn decode_fifo_mid_frame() {
+        // starts with the y and z entries of a sample that was partially read before
+        let mut entries = [0u8; 18];
+        fifo(&[
+            (10, 0), (11, 0),
+            (20, FIFO_X_MARKER), (21, 0), (22, 0),
+            (30, FIFO_X_MARKER),
+        ], &mut entries);
+        let mut samples = [I32x3::new(0, 0, 0); 2];
+
+        let (n, partial) = decode_fifo(&entries, &mut samples);
+
+        assert_eq!(n, 1);
+        assert_eq!(samples[0], I32x3::new(20, 21, 22));
+        assert_eq!(partial, &entries[15..]);
+    }
+
+    #[test]
+    fn decode_fifo_stops_at_empty_marker() {
+        let mut entries = [0u8; 18];
+        fifo(&[
+            (1, FIFO_X_MARKER), (2, 0), (3, 0),
+            (0, FIFO_X_MARKER | FIFO_EMPTY), (0, FIFO_EMPTY), (0, FIFO_EMPTY),
+        ], &mut entries);
+        let mut samples = [I32x3::new(0, 0, 0); 2];
+
+        let (n, partial) = decode_fifo(&entries, &mut samples);
+
+        assert_eq!(n, 1);
+        assert!(partial.is_empty());
+        assert_eq!(samples[0], I32x3::new(1, 2, 3));
+    }
+
+    #[test]
+    fn decode_fifo_empty() {
+        let mut entries = [0u8; 9];
+        fifo(&[(0, FIFO_EMPTY), (0, FIFO_EMPTY), (0, FIFO_EMPTY)], &mut entries);
+        let mut samples = [I32x3::new(0, 0, 0); 1];
+
+        let (n, partial) = decode_fifo(&entries, &mut samples);
+
+        assert_eq!(n, 0);
+        assert!(partial.is_empty());
+    }
+
+    #[test]
+    fn calibrate_zero_g() {
+        let mut adxl355 = measuring(&Config::new());
+        adxl355.interface.accel = [160, -320, 256_000 + 48];
+        let mut delay = MockDelay::default();
+
+        let offsets = adxl355.calibrate_zero_g(4, GravityAxis::PlusZ, &mut delay).unwrap();
+
+        assert_eq!(offsets, I32x3::new(-160, 320, -48));
+        let regs = &adxl355.interface.regs[Register::OFFSET_X_H as usize..=Register::OFFSET_Z_L as usize];
+        assert_eq!(regs, [0xFF, 0xF6, 0x00, 0x14, 0xFF, 0xFD]);
+    }
+
+    #[test]
+    fn calibrate_zero_g_times_out() {
+        let mut adxl355 = measuring(&Config::new());
+        adxl355.interface.data_ready = false;
+        let mut delay = MockDelay::default();
+
+        let result = adxl355.calibrate_zero_g(4, GravityAxis::PlusZ, &mut delay);
+
+        assert!(matches!(result, Err(Error::Timeout)));
+        assert_eq!(delay.ms, DATA_READY_POLL_ATTEMPTS as u32 * DATA_READY_POLL_INTERVAL_MS as u32);
+    }
+
+    fn measuring(config: &Config) -> Adxl355<MockInterface, Measuring> {
+        standby(config).start().map_err(|(e, _)| e).unwrap()
+    }
+
+    #[test]
+    fn self_test_passes_within_limits() {
+        let mut config = Config::new();
+        config.hpf(HPF_CORNER::_247_ODR);
+        let mut adxl355 = measuring(&config);
+        adxl355.interface.accel = [0, 0, 64_000];
+        adxl355.interface.self_test_accel = [19_200, 19_200, 64_000 + 96_000];
+        let filter = adxl355.interface.reg(Register::FILTER);
+        let range = adxl355.interface.reg(Register::RANGE);
+
+        let report = adxl355.self_test(&mut MockDelay::default()).unwrap();
 
-    fn sample_rate(&mut self) -> Result<f32, Error<Self::Error>> {
-        Ok(self.odr.into())
+        assert_eq!(report.delta_mg, I32x3::new(300, 300, 1500));
+        assert_eq!(report.passed, Some(true));
+        let (test_filter, test_range) = adxl355.interface.self_test_conf.unwrap();
+        assert_eq!(test_filter >> 4, HPF_CORNER::NONE.val());
+        assert_eq!(test_range & 0b11, Range::_8G.val());
+        assert_eq!(adxl355.interface.reg(Register::SELF_TEST), 0);
+        assert_eq!(adxl355.interface.reg(Register::FILTER), filter);
+        assert_eq!(adxl355.interface.reg(Register::RANGE), range);
+        assert_eq!(adxl355.interface.reg(Register::POWER_CTL) & 0x01, 0);
     }
 
-    fn accel_norm(&mut self) -> Result<F32x3, Error<Self::Error>> {
-        let raw_data: I32x3 = self.accel_raw()?;
-        let range: f32 = self.range.into(); // range in [g], so 2, 4 or 8
+    #[test]
+    fn self_test_fails_outside_limits() {
+        let mut adxl355 = measuring(&Config::new());
+        adxl355.interface.self_test_accel = [19_200, 0, 96_000];
 
-        let x = (raw_data.x as f32 / ACCEL_MAX_I20 as f32) * range;
-        let y = (raw_data.y as f32 / ACCEL_MAX_I20 as f32) * range;
-        let z = (raw_data.z as f32 / ACCEL_MAX_I20 as f32) * range;
+        let report = adxl355.self_test(&mut MockDelay::default()).unwrap();
 
-        Ok(F32x3::new(x, y, z))
+        assert_eq!(report.delta_mg, I32x3::new(300, 0, 1500));
+        assert_eq!(report.passed, Some(false));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn self_test_clears_self_test_on_error() {
+        let mut config = Config::new();
+        config.hpf(HPF_CORNER::_247_ODR);
+        let mut adxl355 = measuring(&config);
+        adxl355.interface.fail_in_self_test = true;
+        let filter = adxl355.interface.reg(Register::FILTER);
+        let range = adxl355.interface.reg(Register::RANGE);
+
+        let result = adxl355.self_test(&mut MockDelay::default());
+
+        assert!(matches!(result, Err(Error::Bus(()))));
+        assert_eq!(adxl355.interface.reg(Register::SELF_TEST), 0);
+        assert_eq!(adxl355.interface.reg(Register::FILTER), filter);
+        assert_eq!(adxl355.interface.reg(Register::RANGE), range);
+        assert_eq!(adxl355.interface.reg(Register::POWER_CTL) & 0x01, 0);
+    }
+
+    #[test]
+    fn self_test_adxl357_has_no_verdict() {
+        let config = Config::<variant::Adxl357>::default();
+        let mut adxl357 = Adxl357::with_interface(MockInterface::new(), &config)
+            .unwrap()
+            .start()
+            .map_err(|(e, _)| e)
+            .unwrap();
+        adxl357.interface.self_test_accel = [12_800, 12_800, 12_800];
+
+        let report = adxl357.self_test(&mut MockDelay::default()).unwrap();
+
+        assert_eq!(report.delta_mg, I32x3::new(1000, 1000, 1000));
+        assert_eq!(report.passed, None);
+        let (_, test_range) = adxl357.interface.self_test_conf.unwrap();
+        assert_eq!(test_range & 0b11, Range357::_40G.val());
+    }
+
+    #[test]
+    fn set_odr_writes_filter_in_standby() {
+        let mut adxl355 = measuring(&Config::new());
+        adxl355.interface.writes.clear();
+
+        adxl355.set_odr(ODR_LPF::ODR_125_Hz).unwrap();
+
+        assert_eq!(adxl355.interface.writes, [
+            (Register::POWER_CTL.addr(), vec![0x01]),
+            (Register::FILTER.addr(), vec![0x05]),
+            (Register::POWER_CTL.addr(), vec![0x00]),
+        ]);
+        assert_eq!(adxl355.settings.odr, ODR_LPF::ODR_125_Hz);
+    }
+
+    #[test]
+    fn set_odr_in_standby_only_writes_filter() {
+        let mut adxl355 = standby(&Config::new());
+        adxl355.interface.writes.clear();
+
+        adxl355.set_odr(ODR_LPF::ODR_125_Hz).unwrap();
+
+        assert_eq!(adxl355.interface.writes, [(Register::FILTER.addr(), vec![0x05])]);
+    }
+
+    #[test]
+    fn set_range_failure_keeps_settings_and_resumes() {
+        let mut config = Config::new();
+        config.offsets(I32x3::new(1600, 0, 0));
+        let mut adxl355 = measuring(&config);
+        adxl355.interface.fail_writes_to = Some(Register::RANGE);
+
+        let result = adxl355.set_range(Range::_8G);
+
+        assert!(matches!(result, Err(Error::Bus(()))));
+        assert_eq!(adxl355.settings.range, Range::_2G);
+        assert_eq!(adxl355.settings.offsets, [100, 0, 0]);
+        assert_eq!(adxl355.interface.reg(Register::POWER_CTL) & 0x01, 0);
+    }
+
+    #[test]
+    fn apply_failure_keeps_settings() {
+        let mut adxl355 = measuring(&Config::new());
+        adxl355.interface.fail_writes_to = Some(Register::OFFSET_X_H);
+        let mut config = Config::new();
+        config.odr(ODR_LPF::ODR_125_Hz).range(Range::_8G);
+
+        let result = adxl355.apply(&config);
+
+        assert!(matches!(result, Err(Error::Bus(()))));
+        assert_eq!(adxl355.settings.odr, ODR_LPF::default());
+        assert_eq!(adxl355.settings.range, Range::_2G);
+        assert_eq!(adxl355.interface.reg(Register::POWER_CTL) & 0x01, 0);
+    }
+
+    #[test]
+    fn ensure_config_rewrites_drifted_registers() {
+        let mut config = Config::new();
+        config.odr(ODR_LPF::ODR_125_Hz);
+        let mut adxl355 = measuring(&config);
+        adxl355.interface.regs[Register::FILTER as usize] = 0;
+
+        let drift = adxl355.ensure_config().unwrap();
+
+        assert_eq!(drift, ConfigDrift { filter: true, ..Default::default() });
+        assert_eq!(adxl355.recovery_count(), 1);
+        assert_eq!(adxl355.interface.reg(Register::FILTER), ODR_LPF::ODR_125_Hz.val());
+        assert_eq!(adxl355.interface.reg(Register::POWER_CTL) & 0x01, 0);
+
+        assert!(!adxl355.ensure_config().unwrap().any());
+        assert_eq!(adxl355.recovery_count(), 1);
+    }
+
+    #[test]
+    fn ensure_config_recovers_from_device_reset() {
+        let mut adxl355 = measuring(&Config::new());
+        adxl355.interface.write(Register::RESET.addr(), &[RESET_CODE]).unwrap();
+
+        let drift = adxl355.ensure_config().unwrap();
+
+        assert!(drift.power_ctl);
+        assert_eq!(adxl355.recovery_count(), 1);
+        assert_eq!(adxl355.verify_config().unwrap(), ConfigDrift::default());
+        assert_eq!(adxl355.interface.reg(Register::POWER_CTL) & 0x01, 0);
+    }
+
+    #[test]
+    fn reset_waits_for_nvm_busy() {
+        let mut config = Config::new();
+        config.odr(ODR_LPF::ODR_125_Hz);
+        let mut adxl355 = standby(&config);
+        adxl355.interface.nvm_busy_reads = 3;
+        let mut delay = MockDelay::default();
+
+        adxl355.reset(&mut delay).unwrap();
+
+        assert_eq!(delay.ms, 4 * RESET_POLL_INTERVAL_MS as u32);
+        assert_eq!(adxl355.interface.reg(Register::FILTER), ODR_LPF::ODR_125_Hz.val());
+        assert!(!adxl355.verify_config().unwrap().any());
+    }
+
+    #[test]
+    fn reset_times_out() {
+        let mut adxl355 = standby(&Config::new());
+        adxl355.interface.nvm_busy_reads = u32::MAX;
+        let mut delay = MockDelay::default();
+
+        assert!(matches!(adxl355.reset(&mut delay), Err(Error::Timeout)));
+        assert_eq!(delay.ms, RESET_POLL_ATTEMPTS as u32 * RESET_POLL_INTERVAL_MS as u32);
+    }
+}
@@ -0,0 +1,146 @@
+//! Register file model of the device for the unit tests
+
+extern crate std;
+
+use core::convert::Infallible;
+use std::vec::Vec;
+
+use embedded_hal::blocking::delay::DelayMs;
+
+use crate::interface::Interface;
+use crate::register::Register;
+use crate::status::Status;
+use crate::Error;
+
+const REGISTERS: usize = Register::RESET as usize + 1;
+const RESET_CODE: u8 = 0x52;
+
+/// Encodes a sample in raw units as the three bytes of a data register
+pub(crate) fn sample_bytes(value: i32) -> [u8; 3] {
+    let raw = (value << 12) as u32;
+    [(raw >> 24) as u8, (raw >> 16) as u8, (raw >> 8) as u8 & 0xF0]
+}
+
+/// Interface to an emulated device. Register writes are stored and logged, the
+/// acceleration and STATUS are generated from the fields below.
+pub(crate) struct MockInterface {
+    pub(crate) regs: [u8; REGISTERS],
+    /// writes in order, as register and data
+    pub(crate) writes: Vec<(u8, Vec<u8>)>,
+    /// STATUS reports DATA_RDY
+    pub(crate) data_ready: bool,
+    /// number of STATUS reads that report NVM_BUSY
+    pub(crate) nvm_busy_reads: u32,
+    /// acceleration in raw units with SELF_TEST cleared
+    pub(crate) accel: [i32; 3],
+    /// acceleration in raw units with SELF_TEST set
+    pub(crate) self_test_accel: [i32; 3],
+    /// reads of the data registers fail while SELF_TEST is set
+    pub(crate) fail_in_self_test: bool,
+    /// writes to this register fail
+    pub(crate) fail_writes_to: Option<Register>,
+    /// FILTER and RANGE at the time SELF_TEST was last set
+    pub(crate) self_test_conf: Option<(u8, u8)>,
+}
+
+impl MockInterface {
+    pub(crate) fn new() -> Self {
+        MockInterface {
+            regs: reset_regs(),
+            writes: Vec::new(),
+            data_ready: true,
+            nvm_busy_reads: 0,
+            accel: [0; 3],
+            self_test_accel: [0; 3],
+            fail_in_self_test: false,
+            fail_writes_to: None,
+            self_test_conf: None,
+        }
+    }
+
+    pub(crate) fn reg(&self, reg: Register) -> u8 {
+        self.regs[reg as usize]
+    }
+
+    fn read_byte(&mut self, addr: usize) -> Result<u8, Error<(), Infallible>> {
+        let self_test = self.regs[Register::SELF_TEST as usize] != 0;
+
+        if addr == Register::STATUS as usize {
+            let mut status = 0;
+            if self.data_ready {
+                status |= Status::DATA_RDY.bits();
+            }
+            if self.nvm_busy_reads > 0 {
+                self.nvm_busy_reads -= 1;
+                status |= Status::NVM_BUSY.bits();
+            }
+            return Ok(status);
+        }
+
+        if (Register::XDATA3 as usize..=Register::ZDATA1 as usize).contains(&addr) {
+            if self_test && self.fail_in_self_test {
+                return Err(Error::Bus(()));
+            }
+            let accel = if self_test { self.self_test_accel } else { self.accel };
+            let offset = addr - Register::XDATA3 as usize;
+            return Ok(sample_bytes(accel[offset / 3])[offset % 3]);
+        }
+
+        Ok(self.regs[addr])
+    }
+}
+
+impl Interface for MockInterface {
+    type BusError = ();
+    type PinError = Infallible;
+
+    fn read(&mut self, reg: u8, buffer: &mut [u8]) -> Result<(), Error<(), Infallible>> {
+        for (i, byte) in buffer.iter_mut().enumerate() {
+            *byte = self.read_byte(reg as usize + i)?;
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, reg: u8, data: &[u8]) -> Result<(), Error<(), Infallible>> {
+        if self.fail_writes_to.map(|r| r.addr()) == Some(reg) {
+            return Err(Error::Bus(()));
+        }
+        self.writes.push((reg, data.to_vec()));
+
+        if reg == Register::RESET.addr() && data == [RESET_CODE] {
+            self.regs = reset_regs();
+            return Ok(());
+        }
+        self.regs[reg as usize..reg as usize + data.len()].copy_from_slice(data);
+
+        if reg == Register::SELF_TEST.addr() && data[0] != 0 {
+            self.self_test_conf = Some((self.reg(Register::FILTER), self.reg(Register::RANGE)));
+        }
+        Ok(())
+    }
+}
+
+/// Register contents after power up
+fn reset_regs() -> [u8; REGISTERS] {
+    let mut regs = [0u8; REGISTERS];
+    regs[Register::DEVID_AD as usize] = 0xAD;
+    regs[Register::DEVID_MST as usize] = 0x1D;
+    regs[Register::DEVID as usize] = 0xED;
+    regs[Register::REVID as usize] = 0x01;
+    regs[Register::FIFO_SAMPLES as usize] = 0x60;
+    regs[Register::RANGE as usize] = 0x81;
+    regs[Register::POWER_CTL as usize] = 0x01;
+    regs
+}
+
+/// Delay that only counts the requested time
+#[derive(Default)]
+pub(crate) struct MockDelay {
+    pub(crate) ms: u32,
+}
+
+impl DelayMs<u8> for MockDelay {
+    fn delay_ms(&mut self, ms: u8) {
+        self.ms += ms as u32;
+    }
+}
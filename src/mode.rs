@@ -0,0 +1,28 @@
+//! Typestate markers for the ADXL355 operating mode
+
+/// `Standby mode`: no measurements are made and the configuration can be changed
+pub struct Standby;
+
+/// `Measurement mode`: measurements are made at the output data rate
+pub struct Measuring;
+
+/// Operating mode of the [`Adxl355`](crate::Adxl355) driver
+pub trait Mode: private::Sealed {
+    /// `true` if the STANDBY bit in POWER_CTL is cleared in this mode
+    const MEASURING: bool;
+}
+
+impl Mode for Standby {
+    const MEASURING: bool = false;
+}
+
+impl Mode for Measuring {
+    const MEASURING: bool = true;
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for super::Standby {}
+    impl Sealed for super::Measuring {}
+}
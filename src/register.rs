@@ -4,17 +4,29 @@
 // See datasheet https://www.analog.com/media/en/technical-documentation/data-sheets/adxl354_355.pdf
 
 #[allow(dead_code)]
+#[derive(Copy, Clone)]
 #[repr(u8)]
 pub enum Register {
 
+    /// Analog Devices ID (Read Only)
+    ///
+    /// "This register contains the Analog Devices ID, 0xAD."
     DEVID_AD = 0x00,
+    /// Analog Devices MEMS ID (Read Only)
+    ///
+    /// "This register contains the Analog Devices MEMS ID, 0x1D."
     DEVID_MST = 0x01,
     /// Device ID (Read Only)
     ///
     /// "The DEVID register holds a fixed device ID code of 0xED (355 octal)."
     DEVID = 0x02,
+    /// Product revision ID (Read Only)
     REVID = 0x03,
+    /// Status flags (Read Only)
     STATUS = 0x04,
+    /// Number of valid entries in the FIFO (Read Only)
+    ///
+    /// Each axis counts as one entry, so a complete x, y, z sample takes three entries.
     FIFO_ENTRIES = 0x05,
     TEMP2 = 0x06,
     TEMP1 = 0x07,
@@ -27,24 +39,46 @@ pub enum Register {
     ZDATA3 = 0x0E,
     ZDATA2 = 0x0F,
     ZDATA1 = 0x10,
+    /// FIFO read access (Read Only)
+    ///
+    /// Every entry is three bytes, left aligned like the XDATA registers. Bit 0 of
+    /// the last byte marks an x-axis entry and bit 1 is set when the FIFO is empty.
     FIFO_DATA = 0x11,
+    /// Offset added to the x-axis data, in twos complement
+    ///
+    /// The significance of OFFSET[15:0] matches the significance of DATA[19:4].
     OFFSET_X_H = 0x1E,
     OFFSET_X_L = 0x1F,
     OFFSET_Y_H = 0x20,
     OFFSET_Y_L = 0x21,
     OFFSET_Z_H = 0x22,
     OFFSET_Z_L = 0x23,
+    /// Activity enable per axis (bit 0 = x, bit 1 = y, bit 2 = z)
     ACT_EN = 0x24,
+    /// Activity threshold, compared against bits 18 to 3 of the acceleration data
     ACT_THRESH_H = 0x25,
     ACT_THRESH_L = 0x26,
+    /// Number of consecutive events above threshold required to detect activity
     ACT_COUNT =  0x27,
     FILTER = 0x28,
+    /// FIFO watermark in entries, 1 to 96 (default 0x60)
     FIFO_SAMPLES = 0x29,
+    /// Interrupt pin function map
+    ///
+    /// Bits 0-3 enable DATA_RDY, FIFO_FULL, FIFO_OVR and Activity on INT1,
+    /// bits 4-7 enable the same sources on INT2.
     INT_MAP = 0x2A,
+    /// External clock (bit 2, EXT_CLK) and external synchronization (bits 1-0, EXT_SYNC)
     SYNC = 0x2B,
+    /// I2C speed (bit 7), interrupt polarity (bit 6) and measurement range (bits 1-0)
     RANGE = 0x2C,
+    /// DRDY_OFF (bit 2), TEMP_OFF (bit 1) and STANDBY (bit 0)
     POWER_CTL = 0x2D,
+    /// Self test mode (bit 0, ST1) and self test force (bit 1, ST2)
     SELF_TEST = 0x2E,
+    /// Reset (Write Only)
+    ///
+    /// "Write Code 0x52 to reset the device, similar to a power-on reset (POR)."
     RESET = 0x2F
 }
 
@@ -0,0 +1,429 @@
+//! Driver configuration shared by the blocking and async drivers
+
+use core::convert::TryFrom;
+
+use crate::conf::*;
+use crate::register::Register;
+use crate::variant::{RangeSetting, Variant};
+use crate::I32x3;
+#[cfg(feature = "float")]
+use crate::F32x3;
+
+pub(crate) const SYNC_EXT_CLK: u8 = 0x04;
+pub(crate) const INTERNAL_CLOCK_HZ: u32 = 1_024_000;
+
+pub(crate) const RANGE_INT_POL_SHIFT: u8 = 6;
+pub(crate) const RANGE_I2C_HS: u8 = 0x80;
+
+pub(crate) const POWER_CTL_STANDBY: u8 = 0x01;
+pub(crate) const POWER_CTL_TEMP_OFF: u8 = 0x02;
+pub(crate) const POWER_CTL_DRDY_OFF: u8 = 0x04;
+
+pub(crate) const FIFO_CAPACITY: usize = 96; // entries, one per axis
+
+pub(crate) const OFFSET_SHIFT: u8 = 4; // offset LSB = 16 data LSB
+
+#[cfg(feature = "float")]
+pub(crate) const STANDARD_GRAVITY: f32 = 9.806_65; // m/s²
+
+pub(crate) const CONFIG_REGS: usize = Register::POWER_CTL as usize - Register::OFFSET_X_H as usize + 1;
+
+/// Register blocks written to configure the device, in order. POWER_CTL is
+/// written last, so measurements start with the new configuration.
+pub(crate) const CONFIG_SEQUENCE: [(Register, Register); 8] = [
+    (Register::FILTER, Register::FILTER),
+    (Register::RANGE, Register::RANGE),
+    (Register::FIFO_SAMPLES, Register::FIFO_SAMPLES),
+    (Register::INT_MAP, Register::INT_MAP),
+    (Register::ACT_EN, Register::ACT_COUNT),
+    (Register::OFFSET_X_H, Register::OFFSET_Z_L),
+    (Register::SYNC, Register::SYNC),
+    (Register::POWER_CTL, Register::POWER_CTL),
+];
+
+/// Configuration the driver keeps in sync with the device
+#[derive(Copy, Clone)]
+pub(crate) struct Settings<V: Variant> {
+    pub(crate) odr: ODR_LPF,
+    pub(crate) hpf: HPF_CORNER,
+    pub(crate) range: V::Range,
+    pub(crate) temp_cal: TempCalibration,
+    pub(crate) fifo_watermark: u8,
+    pub(crate) int_map: InterruptMap,
+    pub(crate) int_pol: InterruptPolarity,
+    pub(crate) activity: ActivityConfig,
+    pub(crate) offsets: [i16; 3],
+    pub(crate) sync: SyncMode,
+    pub(crate) ext_clk: Option<u32>,
+    pub(crate) temp_off: bool,
+    pub(crate) drdy_off: bool,
+    #[cfg(feature = "float")]
+    pub(crate) scale_cal: ScaleCalibration,
+    #[cfg(feature = "float")]
+    pub(crate) gravity: f32,
+}
+
+impl<V: Variant> Settings<V> {
+    /// Settings from `config`, with the defaults for unset values.
+    /// Returns `None` if a value is out of range.
+    pub(crate) fn new(config: &Config<V>) -> Option<Self> {
+        let settings = Settings {
+            odr: ODR_LPF::default(),
+            hpf: HPF_CORNER::default(),
+            range: V::Range::default(),
+            temp_cal: V::TEMP_CALIBRATION,
+            fifo_watermark: FIFO_CAPACITY as u8,
+            int_map: InterruptMap::default(),
+            int_pol: InterruptPolarity::default(),
+            activity: ActivityConfig::default(),
+            offsets: [0; 3],
+            sync: SyncMode::default(),
+            ext_clk: None,
+            temp_off: false,
+            drdy_off: false,
+            #[cfg(feature = "float")]
+            scale_cal: ScaleCalibration::default(),
+            #[cfg(feature = "float")]
+            gravity: STANDARD_GRAVITY,
+        };
+        settings.with_config(config)
+    }
+
+    /// Copy of these settings with the values set in `config` taken over.
+    /// Returns `None` if a value is out of range.
+    ///
+    /// Offsets not set in `config` are rescaled to a new range, so they keep
+    /// correcting the same acceleration.
+    pub(crate) fn with_config(&self, config: &Config<V>) -> Option<Self> {
+        let mut settings = *self;
+
+        settings.range = config.range.unwrap_or(self.range);
+        settings.activity = config.activity.unwrap_or(self.activity);
+        settings.fifo_watermark = config.fifo_watermark.unwrap_or(self.fifo_watermark);
+        if !valid_fifo_watermark(settings.fifo_watermark) || settings.activity.threshold_val(settings.range).is_none() {
+            return None;
+        }
+        settings.offsets = match config.offsets {
+            Some(offsets) => offset_regs(offsets)?,
+            None => rescale_offsets(self.offsets, self.range.lsb_per_g(), settings.range.lsb_per_g())?,
+        };
+
+        settings.odr = config.odr.unwrap_or(self.odr);
+        settings.hpf = config.hpf.unwrap_or(self.hpf);
+        settings.temp_cal = config.temp_cal.unwrap_or(self.temp_cal);
+        settings.int_map = config.int_map.unwrap_or(self.int_map);
+        settings.int_pol = config.int_pol.unwrap_or(self.int_pol);
+        settings.sync = config.sync.unwrap_or(self.sync);
+        settings.ext_clk = config.ext_clk.unwrap_or(self.ext_clk);
+        settings.temp_off = config.temp_off.unwrap_or(self.temp_off);
+        settings.drdy_off = config.drdy_off.unwrap_or(self.drdy_off);
+        #[cfg(feature = "float")]
+        {
+            settings.scale_cal = config.scale_cal.unwrap_or(self.scale_cal);
+            settings.gravity = config.gravity.unwrap_or(self.gravity);
+        }
+        Some(settings)
+    }
+
+    pub(crate) fn filter_reg(&self) -> u8 {
+        (self.hpf.val() << 4) | self.odr.val()
+    }
+
+    pub(crate) fn range_reg(&self, high_speed: bool) -> u8 {
+        let i2c_hs = if high_speed { RANGE_I2C_HS } else { 0 };
+        i2c_hs | (self.int_pol.val() << RANGE_INT_POL_SHIFT) | self.range.val()
+    }
+
+    pub(crate) fn power_ctl_reg(&self, measuring: bool) -> u8 {
+        let mut reg = 0;
+        if !measuring {
+            reg |= POWER_CTL_STANDBY;
+        }
+        if self.temp_off {
+            reg |= POWER_CTL_TEMP_OFF;
+        }
+        if self.drdy_off {
+            reg |= POWER_CTL_DRDY_OFF;
+        }
+        reg
+    }
+
+    pub(crate) fn sync_reg(&self) -> u8 {
+        let ext_clk = if self.ext_clk.is_some() { SYNC_EXT_CLK } else { 0 };
+        ext_clk | self.sync.val()
+    }
+
+    /// Expected contents of the registers from OFFSET_X_H up to POWER_CTL
+    pub(crate) fn config_regs(&self, measuring: bool, high_speed: bool) -> [u8; CONFIG_REGS] {
+        let mut regs = [0u8; CONFIG_REGS];
+        let mut set = |r: Register, val: u8| regs[config_index(r)] = val;
+
+        let [x_h, x_l] = self.offsets[0].to_be_bytes();
+        let [y_h, y_l] = self.offsets[1].to_be_bytes();
+        let [z_h, z_l] = self.offsets[2].to_be_bytes();
+        set(Register::OFFSET_X_H, x_h);
+        set(Register::OFFSET_X_L, x_l);
+        set(Register::OFFSET_Y_H, y_h);
+        set(Register::OFFSET_Y_L, y_l);
+        set(Register::OFFSET_Z_H, z_h);
+        set(Register::OFFSET_Z_L, z_l);
+
+        let [thresh_h, thresh_l] = self.activity.threshold_val(self.range).unwrap_or_default().to_be_bytes();
+        set(Register::ACT_EN, self.activity.enable_val());
+        set(Register::ACT_THRESH_H, thresh_h);
+        set(Register::ACT_THRESH_L, thresh_l);
+        set(Register::ACT_COUNT, self.activity.count);
+
+        set(Register::FILTER, self.filter_reg());
+        set(Register::FIFO_SAMPLES, self.fifo_watermark);
+        set(Register::INT_MAP, self.int_map.val());
+        set(Register::SYNC, self.sync_reg());
+        set(Register::RANGE, self.range_reg(high_speed));
+        set(Register::POWER_CTL, self.power_ctl_reg(measuring));
+        regs
+    }
+
+    /// Decodes the registers from OFFSET_X_H up to POWER_CTL.
+    /// Fails with the register value if a field holds a reserved value.
+    pub(crate) fn decode(&self, regs: &[u8; CONFIG_REGS]) -> Result<Config<V>, u8> {
+        let reg = |r: Register| regs[config_index(r)];
+
+        let range = V::Range::try_from(reg(Register::RANGE) & 0b11)?;
+        let odr = ODR_LPF::try_from(reg(Register::FILTER) & 0x0F)?;
+        let hpf = HPF_CORNER::try_from((reg(Register::FILTER) >> 4) & 0x07)?;
+        let sync = SyncMode::try_from(reg(Register::SYNC) & 0b11)?;
+        let int_pol = if reg(Register::RANGE) & (1 << RANGE_INT_POL_SHIFT) != 0 {
+            InterruptPolarity::ActiveHigh
+        } else {
+            InterruptPolarity::ActiveLow
+        };
+
+        let act_en = reg(Register::ACT_EN);
+        let act_thresh = u16::from_be_bytes([reg(Register::ACT_THRESH_H), reg(Register::ACT_THRESH_L)]);
+        let activity = ActivityConfig {
+            x: act_en & 0b001 != 0,
+            y: act_en & 0b010 != 0,
+            z: act_en & 0b100 != 0,
+            threshold_mg: (act_thresh as u64 * 8 * 1000 / range.lsb_per_g() as u64) as u32,
+            count: reg(Register::ACT_COUNT),
+        };
+
+        let offset = |h: Register, l: Register| (i16::from_be_bytes([reg(h), reg(l)]) as i32) << OFFSET_SHIFT;
+        let offsets = I32x3::new(
+            offset(Register::OFFSET_X_H, Register::OFFSET_X_L),
+            offset(Register::OFFSET_Y_H, Register::OFFSET_Y_L),
+            offset(Register::OFFSET_Z_H, Register::OFFSET_Z_L),
+        );
+
+        let mut config = Config::default();
+        config.range(range)
+            .odr(odr)
+            .hpf(hpf)
+            .fifo_watermark(reg(Register::FIFO_SAMPLES))
+            .interrupts(InterruptMap::from(reg(Register::INT_MAP)))
+            .interrupt_polarity(int_pol)
+            .activity(activity)
+            .sync(sync)
+            .temp_off(reg(Register::POWER_CTL) & POWER_CTL_TEMP_OFF != 0)
+            .drdy_off(reg(Register::POWER_CTL) & POWER_CTL_DRDY_OFF != 0)
+            .offsets(offsets);
+        if reg(Register::SYNC) & SYNC_EXT_CLK != 0 {
+            config.external_clock(self.ext_clk.unwrap_or(INTERNAL_CLOCK_HZ));
+        } else {
+            config.internal_clock();
+        }
+        Ok(config)
+    }
+
+    /// Scales a raw reading to µg by the nominal sensitivity of the active range
+    pub(crate) fn micro_g(&self, raw: I32x3) -> I32x3 {
+        let lsb_per_g = self.range.lsb_per_g() as i64;
+        let micro_g = |raw: i32| (raw as i64 * 1_000_000 / lsb_per_g) as i32;
+
+        I32x3::new(micro_g(raw.x), micro_g(raw.y), micro_g(raw.z))
+    }
+
+    /// Scales a raw reading to g by the nominal sensitivity of the active
+    /// range and corrects it by the scale calibration
+    #[cfg(feature = "float")]
+    pub(crate) fn norm(&self, raw: I32x3) -> F32x3 {
+        let lsb_per_g = self.range.lsb_per_g() as f32;
+
+        let x = raw.x as f32 / lsb_per_g;
+        let y = raw.y as f32 / lsb_per_g;
+        let z = raw.z as f32 / lsb_per_g;
+
+        self.scale_cal.apply(F32x3::new(x, y, z))
+    }
+
+    /// Output data rate in Hz, scaled by the external clock frequency if used
+    #[cfg(feature = "float")]
+    pub(crate) fn sample_rate(&self) -> f32 {
+        let odr: f32 = self.odr.into();
+        match self.ext_clk {
+            Some(hz) => odr * hz as f32 / INTERNAL_CLOCK_HZ as f32,
+            None => odr,
+        }
+    }
+}
+
+/// Position of a register in the block from OFFSET_X_H up to POWER_CTL
+pub(crate) fn config_index(reg: Register) -> usize {
+    reg as usize - Register::OFFSET_X_H as usize
+}
+
+/// Converts offsets in raw units to offset register values, rounded to 16 LSB
+pub(crate) fn offset_regs(offsets: I32x3) -> Option<[i16; 3]> {
+    let mut regs = [0i16; 3];
+    for (reg, offset) in regs.iter_mut().zip([offsets.x, offsets.y, offsets.z].iter()) {
+        let val = offset.saturating_add(1 << (OFFSET_SHIFT - 1)) >> OFFSET_SHIFT;
+        if val < i16::MIN as i32 || val > i16::MAX as i32 {
+            return None;
+        }
+        *reg = val as i16;
+    }
+    Some(regs)
+}
+
+/// Rescales offset register values from a range with `from` LSB/g to one with
+/// `to` LSB/g, rounded to the nearest register value
+pub(crate) fn rescale_offsets(offsets: [i16; 3], from: u32, to: u32) -> Option<[i16; 3]> {
+    let mut regs = [0i16; 3];
+    for (reg, &offset) in regs.iter_mut().zip(offsets.iter()) {
+        let scaled = offset as i64 * to as i64;
+        let half = if scaled < 0 { -(from as i64 / 2) } else { from as i64 / 2 };
+        let val = (scaled + half) / from as i64;
+        if val < i16::MIN as i64 || val > i16::MAX as i64 {
+            return None;
+        }
+        *reg = val as i16;
+    }
+    Some(regs)
+}
+
+pub(crate) fn valid_fifo_watermark(entries: u8) -> bool {
+    (1..=FIFO_CAPACITY as u8).contains(&entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variant;
+
+    #[test]
+    fn offset_regs_round_to_register_resolution() {
+        assert_eq!(offset_regs(I32x3::new(24, 23, -24)), Some([2, 1, -1]));
+        assert_eq!(
+            offset_regs(I32x3::new(i16::MAX as i32 * 16, i16::MIN as i32 * 16, 0)),
+            Some([i16::MAX, i16::MIN, 0])
+        );
+        assert_eq!(offset_regs(I32x3::new((i16::MAX as i32 + 1) * 16, 0, 0)), None);
+        assert_eq!(offset_regs(I32x3::new(0, 0, (i16::MIN as i32 - 1) * 16)), None);
+    }
+
+    fn activity(threshold_mg: u32) -> ActivityConfig {
+        ActivityConfig { x: true, y: false, z: true, threshold_mg, count: 3 }
+    }
+
+    #[test]
+    fn decode_round_trips_config_regs() {
+        let mut config = Config::new();
+        config.range(Range::_4G)
+            .odr(ODR_LPF::ODR_125_Hz)
+            .hpf(HPF_CORNER::_0_954_ODR)
+            .fifo_watermark(30)
+            .interrupts(InterruptMap::from(0b0110_0101))
+            .interrupt_polarity(InterruptPolarity::ActiveHigh)
+            .activity(activity(1000))
+            .sync(SyncMode::ExternalInterpolated)
+            .external_clock(1_000_000)
+            .temp_off(true)
+            .drdy_off(true)
+            .offsets(I32x3::new(1600, -16, 0));
+        let settings = Settings::new(&config).unwrap();
+
+        let regs = settings.config_regs(true, false);
+
+        assert_eq!(settings.decode(&regs), Ok(config));
+    }
+
+    #[test]
+    fn decode_round_trips_config_regs_adxl357() {
+        let mut config = Config::<variant::Adxl357>::default();
+        config.range(Range357::_20G)
+            .odr(ODR_LPF::ODR_1000_Hz)
+            .hpf(HPF_CORNER::NONE)
+            .fifo_watermark(96)
+            .interrupts(InterruptMap::default())
+            .interrupt_polarity(InterruptPolarity::ActiveLow)
+            .activity(activity(1000))
+            .sync(SyncMode::Internal)
+            .internal_clock()
+            .temp_off(false)
+            .drdy_off(false)
+            .offsets(I32x3::new(-3200, 0, 160));
+        let settings = Settings::new(&config).unwrap();
+
+        let regs = settings.config_regs(false, true);
+
+        assert_eq!(regs[config_index(Register::RANGE)] & RANGE_I2C_HS, RANGE_I2C_HS);
+        assert_eq!(settings.decode(&regs), Ok(config));
+    }
+
+    #[test]
+    fn decode_rejects_reserved_values() {
+        let settings = Settings::new(&Config::new()).unwrap();
+        let regs = settings.config_regs(false, false);
+        let with = |r: Register, val: u8| {
+            let mut regs = regs;
+            regs[config_index(r)] = val;
+            settings.decode(&regs)
+        };
+
+        assert_eq!(with(Register::RANGE, 0x00), Err(0x00));
+        assert_eq!(with(Register::FILTER, 0x0B), Err(0x0B));
+        assert_eq!(with(Register::FILTER, 0x70), Err(0x07));
+        assert_eq!(with(Register::SYNC, 0x03), Err(0x03));
+    }
+
+    #[test]
+    fn rescale_offsets_rounds_half_away_from_zero() {
+        assert_eq!(rescale_offsets([100, -100, 1], 256_000, 128_000), Some([50, -50, 1]));
+        assert_eq!(rescale_offsets([50, -50, 1], 128_000, 256_000), Some([100, -100, 2]));
+        assert_eq!(rescale_offsets([i16::MAX, 0, 0], 64_000, 128_000), None);
+    }
+
+    #[test]
+    fn with_config_rescales_offsets_to_new_range() {
+        let mut config = Config::new();
+        config.offsets(I32x3::new(1600, -1600, 0));
+        let settings = Settings::new(&config).unwrap();
+        assert_eq!(settings.offsets, [100, -100, 0]);
+
+        let mut config = Config::new();
+        config.range(Range::_8G);
+        let settings = settings.with_config(&config).unwrap();
+        assert_eq!(settings.range, Range::_8G);
+        assert_eq!(settings.offsets, [25, -25, 0]);
+    }
+
+    #[test]
+    fn with_config_rejects_invalid_values() {
+        let settings = Settings::new(&Config::new()).unwrap();
+
+        let mut config = Config::new();
+        config.fifo_watermark(0);
+        assert!(settings.with_config(&config).is_none());
+
+        let mut config = Config::new();
+        config.activity(activity(20_000));
+        assert!(settings.with_config(&config).is_none());
+
+        let mut config = Config::new();
+        config.range(Range::_8G).activity(activity(4000));
+        let settings = settings.with_config(&config).unwrap();
+        let mut config = Config::new();
+        config.range(Range::_2G);
+        assert!(settings.with_config(&config).is_none());
+    }
+}
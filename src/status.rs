@@ -0,0 +1,75 @@
+//! ADXL355 STATUS register decoding
+
+use core::ops::BitOr;
+
+/// Contents of the STATUS register
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct Status(u8);
+
+impl Status {
+    /// A complete x-axis, y-axis, and z-axis measurement was made and results can be read
+    pub const DATA_RDY: Status = Status(1 << 0);
+    /// FIFO watermark is reached
+    pub const FIFO_FULL: Status = Status(1 << 1);
+    /// FIFO has overrun, and the oldest data is lost
+    pub const FIFO_OVR: Status = Status(1 << 2);
+    /// Activity, as defined in the ACT_THRESH and ACT_COUNT registers, is detected
+    pub const ACTIVITY: Status = Status(1 << 3);
+    /// NVM controller is busy with a refresh, programming, or built in self test (BIST)
+    pub const NVM_BUSY: Status = Status(1 << 4);
+
+    const ALL: u8 = 0x1F;
+
+    /// Creates a status from the raw register value, ignoring reserved bits
+    pub fn from_bits_truncate(bits: u8) -> Self {
+        Status(bits & Self::ALL)
+    }
+
+    /// Returns the raw register value
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Returns `true` if all flags in `other` are set
+    pub fn contains(self, other: Status) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns `true` if no flag is set
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// New data is available
+    pub fn data_ready(self) -> bool {
+        self.contains(Status::DATA_RDY)
+    }
+
+    /// The FIFO watermark is reached
+    pub fn fifo_full(self) -> bool {
+        self.contains(Status::FIFO_FULL)
+    }
+
+    /// The FIFO has overrun
+    pub fn fifo_overrun(self) -> bool {
+        self.contains(Status::FIFO_OVR)
+    }
+
+    /// Activity was detected
+    pub fn activity(self) -> bool {
+        self.contains(Status::ACTIVITY)
+    }
+
+    /// The NVM controller is busy
+    pub fn nvm_busy(self) -> bool {
+        self.contains(Status::NVM_BUSY)
+    }
+}
+
+impl BitOr for Status {
+    type Output = Status;
+
+    fn bitor(self, rhs: Status) -> Status {
+        Status(self.0 | rhs.0)
+    }
+}